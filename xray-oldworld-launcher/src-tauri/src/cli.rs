@@ -0,0 +1,399 @@
+use std::path::Path;
+
+use crate::config::change_journal::ChangeOrigin;
+use crate::config::console_schema;
+use crate::config::launcher_config::LauncherConfig;
+use crate::game;
+use crate::game::health::{HealthReport, Severity};
+use crate::game::launcher::LaunchPlan;
+use crate::game::paths::GamePaths;
+use crate::logging;
+use crate::{AppState, OptionChange, OptionStorage};
+
+pub const EXIT_OK: i32 = 0;
+/// The action itself failed (I/O error, spawn failure, unknown command for `--get`).
+pub const EXIT_FAILED: i32 = 1;
+/// Bad command line.
+pub const EXIT_USAGE: i32 = 2;
+/// No usable install: paths didn't resolve or `--validate` found errors.
+pub const EXIT_INVALID_INSTALL: i32 = 3;
+
+const USAGE: &str = "\
+Usage: xray-oldworld-launcher [--game-root <dir>] [action...] [-- <game args>]
+
+Without actions the launcher window opens (using --game-root if given).
+Arguments the launcher doesn't know, and everything after --, are passed to
+the game after the custom arguments (e.g. Steam launch options).
+
+Actions:
+  --set <cmd>=<value>    Set a user.ltx command (repeatable)
+  --get <cmd>            Print a user.ltx value (repeatable)
+  --reset-user-ltx       Replace user.ltx with user_default.ltx
+  --clear-shader-cache   Delete the compiled shader cache
+  --validate             Check the install; exit code 3 if it can't launch
+  --print-launch-plan    Print the command --launch would run
+  --launch               Start the game
+
+Options:
+  --game-root <dir>      Use this game folder for this run only
+  --play                 Validate and launch the game, opening the window only
+                         if that fails
+  --settings             Open the window even when auto-launch is on (holding
+                         Shift at startup does the same on Windows)
+  --config <key>=<value> Override a launcher setting for this run only
+  -h, --help             Show this help
+
+Actions run in the order listed above, stopping at the first failure.
+Exit codes: 0 ok, 1 failed, 2 bad usage, 3 invalid install.";
+
+/// What the command line asked for.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CliArgs {
+    pub game_root: Option<String>,
+    pub sets: Vec<(String, String)>,
+    pub gets: Vec<String>,
+    pub reset_user_ltx: bool,
+    pub clear_shader_cache: bool,
+    pub validate: bool,
+    pub print_launch_plan: bool,
+    pub launch: bool,
+    /// Auto-launch: like `--launch`, but opens the window on failure.
+    pub play: bool,
+    /// Open the window even if auto-launch is on.
+    pub settings: bool,
+    /// Forwarded to the game after `custom_args`.
+    pub game_args: Vec<String>,
+    pub help: bool,
+}
+
+impl CliArgs {
+    /// Run headless instead of opening the window.
+    pub fn is_headless(&self) -> bool {
+        self.help
+            || !self.sets.is_empty()
+            || !self.gets.is_empty()
+            || self.reset_user_ltx
+            || self.clear_shader_cache
+            || self.validate
+            || self.print_launch_plan
+            || self.launch
+    }
+}
+
+/// Parse the arguments after the program name. `--config` pairs belong to
+/// `config_layers` and are skipped here. Single-dash and bare arguments
+/// (`-nointro`) are for the game. An unknown `--flag` is most likely a typo
+/// of ours: an error when running headless, ignored otherwise so a desktop
+/// shortcut with stray arguments still opens the window.
+pub fn parse(args: &[String]) -> Result<CliArgs, String> {
+    let mut cli = CliArgs::default();
+    let mut unknown = Vec::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if arg == "--" {
+            cli.game_args.extend(iter.by_ref().cloned());
+            break;
+        }
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = |name: &str| -> Result<String, String> {
+            inline
+                .clone()
+                .or_else(|| iter.next().cloned())
+                .ok_or_else(|| format!("{} needs a value", name))
+        };
+
+        match flag {
+            "--game-root" => {
+                let dir = value(flag)?;
+                if dir.trim().is_empty() {
+                    return Err("--game-root needs a directory".to_string());
+                }
+                cli.game_root = Some(dir);
+            }
+            "--set" => {
+                let pair = value(flag)?;
+                let (cmd, val) = pair
+                    .split_once('=')
+                    .ok_or_else(|| format!("--set expects <cmd>=<value>, got '{}'", pair))?;
+                let cmd = cmd.trim();
+                if cmd.is_empty() {
+                    return Err(format!("--set expects <cmd>=<value>, got '{}'", pair));
+                }
+                cli.sets.push((cmd.to_string(), val.trim().to_string()));
+            }
+            "--get" => {
+                let cmd = value(flag)?;
+                cli.gets.push(cmd.trim().to_string());
+            }
+            "--config" => {
+                if inline.is_none() {
+                    iter.next();
+                }
+            }
+            "--reset-user-ltx" => cli.reset_user_ltx = true,
+            "--clear-shader-cache" => cli.clear_shader_cache = true,
+            "--validate" => cli.validate = true,
+            "--print-launch-plan" => cli.print_launch_plan = true,
+            "--launch" => cli.launch = true,
+            "--play" => cli.play = true,
+            "--settings" => cli.settings = true,
+            "-h" | "--help" => cli.help = true,
+            _ if arg.starts_with("--") => unknown.push(arg.clone()),
+            _ => cli.game_args.push(arg.clone()),
+        }
+    }
+
+    if cli.is_headless() && !unknown.is_empty() {
+        return Err(format!("Unknown argument(s): {}", unknown.join(" ")));
+    }
+    if !unknown.is_empty() {
+        logging::log(format!("Ignoring unknown argument(s): {:?}", unknown));
+    }
+    Ok(cli)
+}
+
+pub fn print_usage() {
+    println!("{}", USAGE);
+}
+
+/// Run the headless actions against an initialized `state` and return the
+/// process exit code. `init_error` is why `AppState::initialize` failed, if it did.
+pub fn execute(cli: &CliArgs, state: &AppState, init_error: Option<&str>) -> i32 {
+    if cli.help {
+        print_usage();
+        return EXIT_OK;
+    }
+    logging::log(format!("CLI: {:?}", cli));
+
+    match run_actions(cli, state, init_error) {
+        Ok(()) => EXIT_OK,
+        Err((code, e)) => {
+            logging::log(format!("CLI: failed with exit code {}: {}", code, e));
+            eprintln!("error: {}", e);
+            code
+        }
+    }
+}
+
+fn run_actions(
+    cli: &CliArgs,
+    state: &AppState,
+    init_error: Option<&str>,
+) -> Result<(), (i32, String)> {
+    let paths = match state.get_paths() {
+        Ok(paths) => paths,
+        Err(e) => {
+            // --validate still reports on a configured folder that doesn't resolve
+            let configured = effective_config(state).game_root;
+            if cli.validate
+                && let Some(root) = configured
+            {
+                print_report(&validate(state, Path::new(root.trim())));
+            }
+            return Err((EXIT_INVALID_INSTALL, init_error.map_or(e, str::to_string)));
+        }
+    };
+
+    if cli.reset_user_ltx {
+        crate::reset_user_ltx_recorded(state, &paths).map_err(|e| (EXIT_FAILED, e))?;
+        println!("user.ltx reset to defaults");
+    }
+    if cli.clear_shader_cache {
+        let freed =
+            game::launcher::clear_shader_cache(&paths.appdata).map_err(|e| (EXIT_FAILED, e))?;
+        println!("Shader cache cleared ({} bytes freed)", freed);
+    }
+    if !cli.sets.is_empty() {
+        set(state, &cli.sets)?;
+    }
+    if !cli.gets.is_empty() {
+        get(&paths, &cli.gets)?;
+    }
+    if cli.validate {
+        let report = validate(state, &paths.game_root);
+        print_report(&report);
+        if !report.launchable {
+            return Err((
+                EXIT_INVALID_INSTALL,
+                "the install can't be launched".to_string(),
+            ));
+        }
+    }
+    if cli.print_launch_plan {
+        let plan = launch_plan(state)?;
+        println!("{}", format_plan(&plan));
+        for warning in &plan.warnings {
+            eprintln!("warning: {}", warning.message);
+        }
+        blocked(&plan)?;
+    }
+    if cli.launch {
+        let plan = launch_plan(state)?;
+        blocked(&plan)?;
+        game::launcher::spawn(&plan).map_err(|e| (EXIT_FAILED, e))?;
+    }
+    Ok(())
+}
+
+/// Auto-launch (`--play` or the `autoLaunch` setting): validate and start
+/// the game. On error the caller opens the window, which shows why.
+pub fn auto_launch(state: &AppState, init_error: Option<&str>) -> Result<(), String> {
+    let paths = state
+        .get_paths()
+        .map_err(|e| init_error.map_or(e, str::to_string))?;
+    let report = validate(state, &paths.game_root);
+    if !report.launchable {
+        let problems: Vec<&str> = report
+            .checks
+            .iter()
+            .filter(|c| !c.passed && c.severity == Severity::Error)
+            .map(|c| c.detail.as_str())
+            .collect();
+        return Err(problems.join("; "));
+    }
+    game::launcher::spawn(&crate::current_launch_plan(state)?)
+}
+
+/// All `--set` pairs go through `save_settings` as a single change.
+fn set(state: &AppState, sets: &[(String, String)]) -> Result<(), (i32, String)> {
+    for (cmd, value) in sets {
+        match console_schema::lookup(cmd) {
+            Some(spec) => spec
+                .kind
+                .validate(value)
+                .map_err(|e| (EXIT_USAGE, format!("{}: {}", cmd, e)))?,
+            None => eprintln!("warning: '{}' is not a known console command", cmd),
+        }
+    }
+
+    let changes = sets
+        .iter()
+        .map(|(cmd, value)| OptionChange {
+            path: cmd.clone(),
+            value: value.clone(),
+            storage: OptionStorage::UserLtx { cmd: cmd.clone() },
+        })
+        .collect();
+    let result = crate::save_settings(state, changes, None, true, false, ChangeOrigin::Cli)
+        .map_err(|e| (EXIT_FAILED, e))?;
+    if !result.saved {
+        return Err((EXIT_FAILED, "user.ltx was not saved".to_string()));
+    }
+    for (cmd, value) in sets {
+        println!("{} = {}", cmd, value);
+    }
+    Ok(())
+}
+
+/// Values come from user.ltx, or user_default.ltx before the first save.
+fn get(paths: &GamePaths, cmds: &[String]) -> Result<(), (i32, String)> {
+    let (user, _) = crate::load_user_or_defaults(paths);
+    let values = cmds
+        .iter()
+        .map(|cmd| {
+            user.get(cmd)
+                .ok_or_else(|| (EXIT_FAILED, format!("'{}' is not set", cmd)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // A single value is printed bare so scripts can capture it
+    match values.as_slice() {
+        [value] => println!("{}", value),
+        _ => {
+            for (cmd, value) in cmds.iter().zip(&values) {
+                println!("{} = {}", cmd, value);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn effective_config(state: &AppState) -> LauncherConfig {
+    state
+        .launcher_config
+        .read()
+        .map(|c| c.effective().clone())
+        .unwrap_or_default()
+}
+
+fn validate(state: &AppState, game_root: &Path) -> HealthReport {
+    let appdata = GamePaths::resolve_appdata(game_root);
+    game::health::validate(game_root, &appdata, &effective_config(state))
+}
+
+fn print_report(report: &HealthReport) {
+    println!("{}", report.game_root);
+    for check in &report.checks {
+        let status = match (check.passed, check.severity) {
+            (true, _) => "ok",
+            (false, Severity::Info) => "info",
+            (false, Severity::Warning) => "warning",
+            (false, Severity::Error) => "error",
+        };
+        println!("  [{}] {:?}: {}", status, check.id, check.detail);
+        if !check.passed
+            && let Some(fix) = &check.fix
+        {
+            println!("      {}", fix);
+        }
+    }
+}
+
+fn launch_plan(state: &AppState) -> Result<LaunchPlan, (i32, String)> {
+    crate::current_launch_plan(state).map_err(|e| (EXIT_INVALID_INSTALL, e))
+}
+
+/// A plan `spawn` would refuse means the install is broken.
+fn blocked(plan: &LaunchPlan) -> Result<(), (i32, String)> {
+    match plan.warnings.iter().find(|w| w.blocking) {
+        Some(warning) => Err((EXIT_INVALID_INSTALL, warning.message.clone())),
+        None => Ok(()),
+    }
+}
+
+/// `cwd` and any extra environment first, then the command line.
+pub fn format_plan(plan: &LaunchPlan) -> String {
+    let mut lines = vec![format!("cwd: {}", plan.cwd.display())];
+    lines.extend(plan.env.iter().map(|(name, value)| format!("env: {}={}", name, value)));
+    lines.push(plan.command_line.clone());
+    lines.join("\n")
+}
+
+/// Release builds on Windows are GUI-subsystem apps with no console of
+/// their own; attach to the one we were started from so output shows up.
+#[cfg(windows)]
+pub fn attach_parent_console() {
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    // Fails harmlessly when there is no parent console or one is attached already
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+pub fn attach_parent_console() {}
+
+/// Shift held while the launcher starts: skip auto-launch and open the
+/// window, for players who start it from Steam and can't easily add `--settings`.
+#[cfg(windows)]
+pub fn settings_key_held() -> bool {
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn GetAsyncKeyState(key: i32) -> i16;
+    }
+    const VK_SHIFT: i32 = 0x10;
+    // The high bit is set while the key is down
+    unsafe { GetAsyncKeyState(VK_SHIFT) < 0 }
+}
+
+#[cfg(not(windows))]
+pub fn settings_key_held() -> bool {
+    false
+}
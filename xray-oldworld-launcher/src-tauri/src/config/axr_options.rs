@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::ltx_parser::LtxFile;
+use crate::config::transaction::ConfigTransaction;
+use crate::logging;
+
+/// Section of axr_options.ltx holding the in-game options menu values,
+/// keyed by option path (`video/basic/some_option`).
+pub const SECTION: &str = "options";
+
+pub fn file_path(game_root: &Path) -> PathBuf {
+    game_root
+        .join("gamedata")
+        .join("configs")
+        .join("axr_options.ltx")
+}
+
+/// Load axr_options.ltx. Empty only when the file doesn't exist; a file that
+/// exists but can't be read or parsed is an error, so nothing gets written
+/// over it.
+pub fn load(game_root: &Path) -> Result<LtxFile, String> {
+    let path = file_path(game_root);
+    if !path.exists() {
+        return Ok(LtxFile::new());
+    }
+    LtxFile::parse(&path).map_err(|e| format!("Failed to read axr_options.ltx: {}", e))
+}
+
+/// Option values for display. A damaged file shows as empty; writes go
+/// through `stage_changes`, which refuses it.
+pub fn load_values(game_root: &Path) -> HashMap<String, String> {
+    match load(game_root) {
+        Ok(ltx) => get_all(&ltx),
+        Err(e) => {
+            logging::log(format!("ERROR: {}", e));
+            HashMap::new()
+        }
+    }
+}
+
+/// Check a value imported for an option whose value in this install is
+/// `current`: it must fit on one line of the file and have the same shape
+/// (boolean, number or text) as the value it replaces.
+pub fn check_value(current: &str, value: &str) -> Result<(), String> {
+    if value.chars().any(|c| c.is_control() || c == ';') {
+        return Err("value can't be written to axr_options.ltx".to_string());
+    }
+    let is_bool = |v: &str| v == "true" || v == "false";
+    let is_number = |v: &str| v.parse::<f64>().is_ok();
+    if is_bool(current) && !is_bool(value) {
+        return Err(format!("expected true or false, got '{}'", value));
+    }
+    if is_number(current) && !is_number(value) {
+        return Err(format!("expected a number, got '{}'", value));
+    }
+    Ok(())
+}
+
+/// Stage `changes` (option path, value; empty clears the value) to
+/// axr_options.ltx in `tx`. The file's own text is edited line by line, so
+/// comments, `#include`s and other sections stay as they are. Fails without
+/// staging anything when the file exists but doesn't parse.
+pub fn stage_changes(
+    tx: &mut ConfigTransaction,
+    game_root: &Path,
+    changes: &[(String, String)],
+) -> Result<(), String> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+    load(game_root)?;
+    let path = file_path(game_root);
+    let content = if path.exists() {
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read axr_options.ltx: {}", e))?
+    } else {
+        String::new()
+    };
+    tx.stage(&path, edit_options(&content, changes), true);
+    Ok(())
+}
+
+/// Set each change in the `[options]` section of `content`: matching lines
+/// are replaced in place and new keys are added at the end of the section.
+fn edit_options(content: &str, changes: &[(String, String)]) -> String {
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let line_for = |indent: &str, key: &str, value: &str| {
+        if value.is_empty() {
+            format!("{}{} =", indent, key)
+        } else {
+            format!("{}{} = {}", indent, key, value)
+        }
+    };
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut pending: Vec<&(String, String)> = changes.iter().collect();
+    let mut in_options = false;
+    // Where new keys go: after the section's last key (or its header)
+    let mut insert_at = None;
+
+    for (i, line) in lines.iter_mut().enumerate() {
+        let trimmed = line.trim();
+        if let Some(rest) = trimmed.strip_prefix('[')
+            && let Some(end) = rest.find(']')
+        {
+            in_options = rest[..end].trim() == SECTION;
+            if in_options {
+                insert_at = Some(i + 1);
+            }
+            continue;
+        }
+        if !in_options || trimmed.starts_with(';') {
+            continue;
+        }
+        let Some((key, _)) = trimmed.split_once('=') else {
+            continue;
+        };
+        insert_at = Some(i + 1);
+        if let Some(pos) = pending.iter().position(|(path, _)| path == key.trim()) {
+            let (path, value) = pending.remove(pos);
+            let indent = &line[..line.len() - line.trim_start().len()];
+            *line = line_for(indent, path, value);
+        }
+    }
+
+    let added = pending
+        .iter()
+        .map(|(path, value)| line_for("        ", path, value));
+    match insert_at {
+        Some(at) => {
+            let added: Vec<String> = added.collect();
+            lines.splice(at..at, added);
+        }
+        None => {
+            if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(format!("[{}]", SECTION));
+            lines.extend(added);
+        }
+    }
+
+    let mut output = lines.join(newline);
+    output.push_str(newline);
+    output
+}
+
+/// All option values as path -> value.
+pub fn get_all(ltx: &LtxFile) -> HashMap<String, String> {
+    ltx.section_entries(SECTION)
+        .map(|entries| {
+            entries
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone().unwrap_or_default()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("owl_axr_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(file_path(&root).parent().unwrap()).unwrap();
+        root
+    }
+
+    #[test]
+    fn corrupt_file_is_left_untouched() {
+        let root = game_root("corrupt");
+        let content = "[options]\n#include missing_quotes.ltx\nvideo/basic/renderer = dx11\n";
+        fs::write(file_path(&root), content).unwrap();
+
+        let mut tx = ConfigTransaction::new();
+        let changes = [("video/basic/renderer".to_string(), "dx9".to_string())];
+        assert!(stage_changes(&mut tx, &root, &changes).is_err());
+        assert!(tx.is_empty());
+        assert_eq!(fs::read_to_string(file_path(&root)).unwrap(), content);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn edits_keep_comments_and_includes() {
+        let content = concat!(
+            "; tuned by hand\n#include \"extra.ltx\"\n",
+            "[options]\n    a = 1 ; note\n    b = 2\n\n[other]\nc = 3\n",
+        );
+        let changes = [
+            ("a".to_string(), "5".to_string()),
+            ("d".to_string(), String::new()),
+        ];
+        assert_eq!(
+            edit_options(content, &changes),
+            concat!(
+                "; tuned by hand\n#include \"extra.ltx\"\n",
+                "[options]\n    a = 5\n    b = 2\n        d =\n\n[other]\nc = 3\n",
+            )
+        );
+        assert_eq!(
+            edit_options("", &changes[..1]),
+            "[options]\n        a = 5\n"
+        );
+    }
+}
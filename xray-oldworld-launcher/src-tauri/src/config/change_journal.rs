@@ -0,0 +1,244 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::console_schema;
+use crate::config::defaults_diff::keyed_entries;
+use crate::config::user_ltx::UserLtx;
+use crate::logging;
+
+/// Append-only log of committed user.ltx changes, one JSON record per line.
+/// Lives in the launcher config dir so it survives user.ltx resets.
+const FILENAME: &str = "settings_journal.jsonl";
+
+/// What produced a journal record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeOrigin {
+    Ui,
+    Preset,
+    Reset,
+    Migration,
+    Import,
+    Repair,
+    Undo,
+    Redo,
+    /// `--set` on the command line.
+    Cli,
+}
+
+/// One entry changed by a record. `None` means the line was absent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalChange {
+    /// Entry key (see `console_schema::entry_key`).
+    pub key: String,
+    pub command: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+/// A single committed save.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalRecord {
+    pub id: u64,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub origin: ChangeOrigin,
+    /// For undo/redo records: the id of the record being undone or redone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<u64>,
+    /// Install whose user.ltx changed. Missing in records written before
+    /// installs could be switched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_root: Option<String>,
+    pub changes: Vec<JournalChange>,
+}
+
+pub fn file_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(FILENAME)
+}
+
+/// Records for the install at `game_root`, oldest first. The journal is
+/// shared by all installs, so undo never replays another install's changes.
+pub fn load(config_dir: &Path, game_root: &Path) -> Vec<JournalRecord> {
+    let root = game_root.display().to_string();
+    load_all(config_dir)
+        .into_iter()
+        .filter(|r| r.game_root.as_ref().is_none_or(|r| *r == root))
+        .collect()
+}
+
+/// Read all records, oldest first. Unreadable lines are skipped.
+fn load_all(config_dir: &Path) -> Vec<JournalRecord> {
+    let Ok(content) = fs::read_to_string(file_path(config_dir)) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Entry-level differences between two versions of user.ltx, in file order.
+pub fn diff(before: &UserLtx, after: &UserLtx) -> Vec<JournalChange> {
+    let old = keyed_entries(before);
+    let new = keyed_entries(after);
+
+    let changed = new.iter().filter_map(|(key, (command, value))| {
+        let old_value = old.get(key).map(|(_, v)| v);
+        (old_value != Some(value)).then(|| JournalChange {
+            key: key.clone(),
+            command: command.clone(),
+            old_value: old_value.cloned(),
+            new_value: Some(value.clone()),
+        })
+    });
+    let removed = old
+        .iter()
+        .filter(|(key, _)| !new.contains_key(*key))
+        .map(|(key, (command, value))| JournalChange {
+            key: key.clone(),
+            command: command.clone(),
+            old_value: Some(value.clone()),
+            new_value: None,
+        });
+
+    changed.chain(removed).collect()
+}
+
+/// Append a record for `changes`. Returns `None` if there was nothing to record;
+/// undo/redo records (with a `target`) are always written.
+pub fn append(
+    config_dir: &Path,
+    game_root: &Path,
+    origin: ChangeOrigin,
+    target: Option<u64>,
+    changes: Vec<JournalChange>,
+) -> Result<Option<JournalRecord>, String> {
+    if changes.is_empty() && target.is_none() {
+        return Ok(None);
+    }
+
+    let id = load_all(config_dir).last().map_or(1, |r| r.id + 1);
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let record = JournalRecord {
+        id,
+        timestamp,
+        origin,
+        target,
+        game_root: Some(game_root.display().to_string()),
+        changes,
+    };
+
+    let line = serde_json::to_string(&record)
+        .map_err(|e| format!("Failed to serialize journal record: {}", e))?;
+    let path = file_path(config_dir);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    writeln!(file, "{}", line)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(Some(record))
+}
+
+/// Journal the difference between `before` and `after`. The change is already
+/// on disk at this point, so a journal failure is logged rather than returned.
+pub fn record(
+    config_dir: &Path,
+    game_root: &Path,
+    origin: ChangeOrigin,
+    before: &UserLtx,
+    after: &UserLtx,
+) {
+    if let Err(e) = append(config_dir, game_root, origin, None, diff(before, after)) {
+        logging::log(format!("WARNING: {}", e));
+    }
+}
+
+/// Undo and redo stacks (record ids, most recent last) implied by the journal.
+/// Any new change after an undo clears the redo stack, like an editor.
+fn stacks(records: &[JournalRecord]) -> (Vec<u64>, Vec<u64>) {
+    let mut applied = Vec::new();
+    let mut undone = Vec::new();
+    for record in records {
+        match record.origin {
+            ChangeOrigin::Undo => {
+                if applied.last() == record.target.as_ref() {
+                    undone.extend(applied.pop());
+                }
+            }
+            ChangeOrigin::Redo => {
+                if undone.last() == record.target.as_ref() {
+                    applied.extend(undone.pop());
+                }
+            }
+            _ => {
+                applied.push(record.id);
+                undone.clear();
+            }
+        }
+    }
+    (applied, undone)
+}
+
+fn find(records: &[JournalRecord], id: Option<&u64>) -> Option<JournalRecord> {
+    id.and_then(|id| records.iter().find(|r| r.id == *id)).cloned()
+}
+
+/// The record the next undo would revert.
+pub fn undo_target(records: &[JournalRecord]) -> Option<JournalRecord> {
+    find(records, stacks(records).0.last())
+}
+
+/// The record the next redo would re-apply.
+pub fn redo_target(records: &[JournalRecord]) -> Option<JournalRecord> {
+    find(records, stacks(records).1.last())
+}
+
+/// Revert (`forward == false`) or re-apply (`forward == true`) a record's
+/// changes on `user`. Entries changed since the record (e.g. by the game) are
+/// left alone; their keys are returned.
+pub fn replay(user: &mut UserLtx, record: &JournalRecord, forward: bool) -> Vec<String> {
+    let current = keyed_entries(user);
+    let mut skipped = Vec::new();
+
+    for change in record.changes.iter().rev() {
+        let (expected, target) = if forward {
+            (&change.old_value, &change.new_value)
+        } else {
+            (&change.new_value, &change.old_value)
+        };
+
+        let actual = current.get(&change.key).map(|(_, v)| v);
+        let unchanged = match (actual, expected) {
+            (Some(a), Some(e)) => console_schema::values_equal(a, e),
+            (None, None) => true,
+            _ => false,
+        };
+        if !unchanged {
+            skipped.push(change.key.clone());
+            continue;
+        }
+
+        match target {
+            Some(value) => user.set_entry(&change.key, value),
+            None => {
+                user.remove_entry(&change.key);
+            }
+        }
+    }
+
+    skipped
+}
@@ -0,0 +1,138 @@
+use serde::Serialize;
+
+use crate::config::console_schema;
+use crate::config::user_ltx::{LineView, UserLtx};
+
+/// Commands in user.ltx that the engine no longer knows or has replaced.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandScan {
+    pub findings: Vec<ScanFinding>,
+    /// Command lines inspected.
+    pub checked: usize,
+    /// False when user_default.ltx is missing; unknown commands can't be
+    /// told apart from valid ones then, so only deprecated ones are listed.
+    pub defaults_available: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FindingKind {
+    /// Not in the schema or user_default.ltx — left over from an addon or a typo.
+    Unknown,
+    /// Renamed or dropped by a newer engine build.
+    Deprecated,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanFinding {
+    /// Entry key to pass back for removal (see `console_schema::entry_key`).
+    pub key: String,
+    pub command: String,
+    pub value: String,
+    /// 1-based line number in user.ltx.
+    pub line: usize,
+    pub kind: FindingKind,
+    /// Current form of a deprecated line (`rs_screenmode fullscreen`), if any.
+    pub replacement: Option<String>,
+    /// Closest known command name, for likely typos.
+    pub suggestion: Option<String>,
+}
+
+/// Scan `user` for unknown and deprecated commands. A command is known if the
+/// console schema or `defaults` (user_default.ltx) has it.
+pub fn scan(user: &UserLtx, defaults: &UserLtx) -> CommandScan {
+    let mut known: Vec<&str> = defaults.commands().map(|(name, _)| name).collect();
+    known.extend(console_schema::all().iter().map(|spec| spec.name));
+    known.sort_unstable();
+    known.dedup();
+
+    let defaults_available = defaults.commands().next().is_some();
+    let mut result = CommandScan {
+        findings: Vec::new(),
+        checked: 0,
+        defaults_available,
+    };
+
+    for (idx, line) in user.lines().enumerate() {
+        let LineView::Command { name, value } = line else {
+            continue;
+        };
+        result.checked += 1;
+
+        let key = console_schema::entry_key(name, value);
+        let finding = |kind, replacement, suggestion| ScanFinding {
+            key: key.clone(),
+            command: name.to_string(),
+            value: value.to_string(),
+            line: idx + 1,
+            kind,
+            replacement,
+            suggestion,
+        };
+
+        if console_schema::migration(name).is_some() {
+            match console_schema::migrate(name, value) {
+                None => result
+                    .findings
+                    .push(finding(FindingKind::Deprecated, None, None)),
+                Some((new_name, new_value)) if new_name != name || new_value != value => {
+                    let replacement = format!("{} {}", new_name, new_value);
+                    result
+                        .findings
+                        .push(finding(FindingKind::Deprecated, Some(replacement), None));
+                }
+                Some(_) => {}
+            }
+            continue;
+        }
+
+        let multi_key = console_schema::split_entry_key(&key).1.is_some();
+        if defaults_available && !multi_key && known.binary_search(&name).is_err() {
+            let suggestion = closest(name, &known).map(|s| s.to_string());
+            result
+                .findings
+                .push(finding(FindingKind::Unknown, None, suggestion));
+        }
+    }
+
+    result
+}
+
+/// The known name within a small edit distance of `name`, if one stands out.
+fn closest<'a>(name: &str, known: &[&'a str]) -> Option<&'a str> {
+    // Allow roughly one typo per four characters, at most two
+    let max = (name.len() / 4).clamp(1, 2);
+    known
+        .iter()
+        .map(|k| (edit_distance(name, k), *k))
+        .filter(|(d, _)| *d <= max)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, k)| k)
+}
+
+/// Levenshtein distance, counting a swap of adjacent characters as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
@@ -0,0 +1,372 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::config::launcher_config::LauncherConfig;
+use crate::logging;
+
+/// Launcher defaults a mod ships in the game root.
+pub const MOD_DEFAULTS_FILENAME: &str = "launcher_defaults.json";
+
+/// `OWL_CONFIG_SHADOW_MAP_SIZE=4096` overrides `shadowMapSize`.
+const ENV_PREFIX: &str = "OWL_CONFIG_";
+/// `--config shadowMapSize=4096` (or `--config=...`) on the command line.
+const CLI_FLAG: &str = "--config";
+
+/// Key `get_launcher_config` adds for the per-field sources. The UI sends it
+/// back with the config, so it's never persisted.
+const LAYERS_KEY: &str = "layers";
+
+/// Fields a mod can't set or lock: the game root decides which mod is
+/// loaded in the first place, and the installation registry spans mods.
+const USER_ONLY: &[&str] = &[
+    "gameRoot",
+    "schemaVersion",
+    "installations",
+    "activeInstallation",
+];
+
+/// Where an effective launcher setting came from, lowest priority first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigLayer {
+    /// `LauncherConfig::default()`.
+    Builtin,
+    /// launcher_defaults.json in the game root.
+    Mod,
+    /// launcher_config.json.
+    User,
+    /// Environment or command line, for this run only.
+    Override,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldSource {
+    pub layer: ConfigLayer,
+    /// Locked by the mod: the user's value is ignored and the UI shouldn't
+    /// offer to change it.
+    pub locked: bool,
+}
+
+/// launcher_defaults.json:
+/// `{ "defaults": { "shadowMapSize": 4096 }, "locked": ["useAvx"] }`.
+/// Keys may be camelCase or snake_case.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModDefaults {
+    #[serde(default)]
+    pub defaults: Map<String, Value>,
+    #[serde(default)]
+    pub locked: Vec<String>,
+}
+
+/// The effective config with the source of every field, as returned by
+/// `get_launcher_config`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveConfig {
+    #[serde(flatten)]
+    pub config: LauncherConfig,
+    pub layers: BTreeMap<String, FieldSource>,
+}
+
+/// Builtin defaults, mod defaults, the user's file and overrides, merged in
+/// that order. Locked fields skip the user layer; overrides still apply,
+/// since whoever starts the launcher with them means it.
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    mod_defaults: ModDefaults,
+    user: Map<String, Value>,
+    overrides: Map<String, Value>,
+    effective: LauncherConfig,
+    sources: BTreeMap<String, FieldSource>,
+}
+
+impl ModDefaults {
+    /// Load launcher_defaults.json from `game_root`. Empty if the mod doesn't
+    /// ship one; invalid fields are logged and skipped.
+    pub fn load(game_root: &Path) -> Self {
+        let path = game_root.join(MOD_DEFAULTS_FILENAME);
+        if !path.is_file() {
+            return Self::default();
+        }
+
+        let parsed = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<Self>(&content).map_err(|e| e.to_string()));
+        let raw = match parsed {
+            Ok(raw) => raw,
+            Err(e) => {
+                logging::log(format!("ERROR reading {}: {}", path.display(), e));
+                return Self::default();
+            }
+        };
+
+        let defaults = raw
+            .defaults
+            .into_iter()
+            .map(|(key, value)| (camel_case(&key), value))
+            .filter(|(key, _)| !is_user_only(key, "set"))
+            .collect();
+        let mut locked: Vec<String> = raw
+            .locked
+            .iter()
+            .map(|key| camel_case(key))
+            .filter(|key| !is_user_only(key, "lock"))
+            .collect();
+        locked.sort();
+        locked.dedup();
+
+        let defaults = validated(ConfigLayer::Mod, defaults);
+        logging::log(format!(
+            "Mod launcher defaults: {} field(s), locked: {:?}",
+            defaults.len(),
+            locked
+        ));
+        ModDefaults { defaults, locked }
+    }
+
+    pub fn is_locked(&self, key: &str) -> bool {
+        self.locked.iter().any(|k| k == key)
+    }
+}
+
+impl LayeredConfig {
+    /// Layers without mod defaults; those need the game root, which can
+    /// itself come from `user` or `overrides`.
+    pub fn new(user: Map<String, Value>, overrides: Map<String, Value>) -> Self {
+        let mut config = LayeredConfig {
+            mod_defaults: ModDefaults::default(),
+            user,
+            overrides: validated(ConfigLayer::Override, overrides),
+            effective: LauncherConfig::default(),
+            sources: BTreeMap::new(),
+        };
+        config.rebuild();
+        config
+    }
+
+    pub fn set_mod_defaults(&mut self, mod_defaults: ModDefaults) {
+        for key in &mod_defaults.locked {
+            if self.user.contains_key(key) {
+                logging::log(format!("Ignoring user value for '{}': locked by the mod", key));
+            }
+        }
+        self.mod_defaults = mod_defaults;
+        self.rebuild();
+    }
+
+    /// Drop the override for `key` (camelCase) for the rest of this run.
+    /// Returns whether there was one.
+    pub fn clear_override(&mut self, key: &str) -> bool {
+        let removed = self.overrides.remove(key).is_some();
+        if removed {
+            self.rebuild();
+        }
+        removed
+    }
+
+    /// Replace the user layer, e.g. after saving launcher_config.json.
+    pub fn set_user(&mut self, user: Map<String, Value>) {
+        self.user = user;
+        self.rebuild();
+    }
+
+    pub fn effective(&self) -> &LauncherConfig {
+        &self.effective
+    }
+
+    pub fn view(&self) -> EffectiveConfig {
+        EffectiveConfig {
+            config: self.effective.clone(),
+            layers: self.sources.clone(),
+        }
+    }
+
+    /// The fields to write to launcher_config.json when the UI submits
+    /// `submitted`. Only values that differ from the builtin and mod layers
+    /// are kept, so a later mod update still reaches users who never touched
+    /// a field. Locked fields and untouched overrides keep whatever the user
+    /// file had, so they don't leak into it. The installation registry is
+    /// kept as is, apart from the active entry picking up the edits.
+    pub fn user_fields_for(&self, submitted: &LauncherConfig) -> Map<String, Value> {
+        let mut config = submitted.clone();
+        config.installations = self.effective.installations.clone();
+        config.active_installation = self.effective.active_installation.clone();
+        let mut fields = self.user_fields(&config);
+        LauncherConfig::sync_active_installation(&mut fields);
+        fields
+    }
+
+    /// Like `user_fields_for`, for a change the launcher makes itself
+    /// (editing the registry, changing the game root).
+    pub fn user_fields_with(&self, edit: impl FnOnce(&mut LauncherConfig)) -> Map<String, Value> {
+        let mut config = self.effective.clone();
+        edit(&mut config);
+        let mut fields = self.user_fields(&config);
+        LauncherConfig::sync_active_installation(&mut fields);
+        fields
+    }
+
+    /// Apply `edit`, then switch to installation `name`. The previous
+    /// install's entry is already up to date, since every save syncs it.
+    pub fn user_fields_selecting(
+        &self,
+        name: &str,
+        edit: impl FnOnce(&mut LauncherConfig),
+    ) -> Result<Map<String, Value>, String> {
+        let mut config = self.effective.clone();
+        edit(&mut config);
+        let mut fields = self.user_fields(&config);
+        LauncherConfig::select_installation(&mut fields, name)?;
+        Ok(fields)
+    }
+
+    fn user_fields(&self, config: &LauncherConfig) -> Map<String, Value> {
+        let Ok(Value::Object(fields)) = serde_json::to_value(config) else {
+            return self.user.clone();
+        };
+        let below = self.below_user();
+
+        let mut result = Map::new();
+        for (key, value) in fields {
+            if key == LAYERS_KEY {
+                continue;
+            }
+            let keep_previous = self.mod_defaults.is_locked(&key)
+                || self.overrides.get(&key).is_some_and(|v| *v == value);
+            if keep_previous {
+                if let Some(previous) = self.user.get(&key) {
+                    result.insert(key, previous.clone());
+                }
+                continue;
+            }
+            if key == "schemaVersion" || below.get(&key) != Some(&value) {
+                result.insert(key, value);
+            }
+        }
+        result
+    }
+
+    /// Builtin defaults with the mod layer applied.
+    fn below_user(&self) -> Map<String, Value> {
+        let mut merged = LauncherConfig::default_fields();
+        merged.extend(self.mod_defaults.defaults.clone());
+        merged
+    }
+
+    fn rebuild(&mut self) {
+        let mut merged = LauncherConfig::default_fields();
+        let mut sources: BTreeMap<String, FieldSource> = merged
+            .keys()
+            .map(|key| (key.clone(), ConfigLayer::Builtin))
+            .chain(self.mod_defaults.defaults.keys().map(|key| (key.clone(), ConfigLayer::Mod)))
+            .map(|(key, layer)| {
+                let locked = self.mod_defaults.is_locked(&key);
+                (key, FieldSource { layer, locked })
+            })
+            .collect();
+        merged.extend(self.mod_defaults.defaults.clone());
+
+        let user = self
+            .user
+            .iter()
+            .filter(|(key, _)| !self.mod_defaults.is_locked(key))
+            .map(|(key, value)| (key, value, ConfigLayer::User));
+        let overrides = self
+            .overrides
+            .iter()
+            .map(|(key, value)| (key, value, ConfigLayer::Override));
+        for (key, value, layer) in user.chain(overrides) {
+            merged.insert(key.clone(), value.clone());
+            let locked = self.mod_defaults.is_locked(key);
+            sources.insert(key.clone(), FieldSource { layer, locked });
+        }
+
+        sources.remove("schemaVersion");
+        self.effective = LauncherConfig::from_fields(merged);
+        self.sources = sources;
+    }
+}
+
+/// Overrides from `OWL_CONFIG_*` env vars and `--config key=value` args.
+/// Values are JSON (`true`, `4096`, `"text"`); anything that isn't valid
+/// JSON is taken as a plain string.
+pub fn overrides_from_env_and_args(
+    vars: impl IntoIterator<Item = (String, String)>,
+    args: &[String],
+) -> Map<String, Value> {
+    let from_env = vars.into_iter().filter_map(|(name, value)| {
+        let field = name.strip_prefix(ENV_PREFIX)?;
+        Some((camel_case(&field.to_ascii_lowercase()), value))
+    });
+
+    let mut from_args = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let pair = match arg.strip_prefix(CLI_FLAG) {
+            Some("") => iter.next().map(String::as_str),
+            Some(rest) => rest.strip_prefix('='),
+            None => None,
+        };
+        match pair.and_then(|p| p.split_once('=')) {
+            Some((key, value)) => from_args.push((camel_case(key.trim()), value.to_string())),
+            None if pair.is_some() => {
+                logging::log(format!("Ignoring {} without key=value", CLI_FLAG));
+            }
+            None => {}
+        }
+    }
+
+    // Command line after env, so it wins
+    from_env
+        .chain(from_args)
+        .map(|(key, value)| {
+            let value = serde_json::from_str(&value).unwrap_or(Value::String(value));
+            (key, value)
+        })
+        .collect()
+}
+
+/// Drop fields that don't parse, logging each.
+fn validated(layer: ConfigLayer, fields: Map<String, Value>) -> Map<String, Value> {
+    let (accepted, problems) = LauncherConfig::accept_fields(fields);
+    for problem in problems {
+        logging::log(format!(
+            "WARNING: ignoring {:?} value for '{}': {}",
+            layer,
+            problem.field.unwrap_or_default(),
+            problem.message
+        ));
+    }
+    accepted
+}
+
+fn is_user_only(key: &str, action: &str) -> bool {
+    let user_only = USER_ONLY.contains(&key);
+    if user_only {
+        logging::log(format!("Ignoring mod attempt to {} '{}'", action, key));
+    }
+    user_only
+}
+
+/// `shadow_map_size` -> `shadowMapSize`; camelCase keys pass through.
+fn camel_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' {
+            upper = !out.is_empty();
+        } else if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -0,0 +1,322 @@
+use serde::{Deserialize, Serialize};
+
+/// Console commands the launcher knows how to present and validate.
+/// Mirrors the option definitions on the UI side (OptionDefinitions/*.cs);
+/// commands not listed here are still valid in user.ltx, they just have no
+/// display name or value constraints.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub label: &'static str,
+    pub group: SchemaGroup,
+    pub kind: ValueKind,
+}
+
+/// Options page a command belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SchemaGroup {
+    Video,
+    Sound,
+    Control,
+}
+
+/// Accepted values for a console command.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub enum ValueKind {
+    /// on/off style toggle. `numeric` commands are written as 1/0 by the UI.
+    Bool { numeric: bool },
+    /// Numeric value within an inclusive range.
+    Range { min: f64, max: f64 },
+    /// One of a fixed set of tokens.
+    Choice(&'static [&'static str]),
+}
+
+/// Renderer commands whose change invalidates compiled shaders
+/// (lighting model, material pipeline, MSAA and tessellation variants).
+const SHADER_CACHE_COMMANDS: &[&str] = &[
+    "r4_lighting_style",
+    "r4_material_style",
+    "r4_enable_tessellation",
+    "r3_msaa",
+    "r2_ssao_mode",
+    "r2_steep_parallax",
+    "r3_terrain_quality",
+];
+
+/// Commands the engine only reads at startup (beyond the shader ones).
+const RESTART_COMMANDS: &[&str] = &[
+    "rs_screenmode",
+    "r4_hdr10_on",
+    "r4_hires_rts",
+    "r3_gi",
+    "snd_audio_effects_quality",
+];
+
+/// Commands that appear once per action (`bind forward kW`, `bind_sec ...`).
+/// For these the first value token is part of the entry's identity.
+const MULTI_KEY_COMMANDS: &[&str] = &["bind", "bind_sec", "bind_gpad"];
+
+/// A command renamed or dropped since vanilla Anomaly / older OWA builds.
+#[derive(Debug, Clone, Copy)]
+pub struct CommandMigration {
+    pub old: &'static str,
+    /// Replacement command; `None` when the command no longer exists.
+    pub new: Option<&'static str>,
+    /// Old value -> new value; values not listed carry over unchanged.
+    pub values: &'static [(&'static str, &'static str)],
+}
+
+/// Applied when importing another install's user.ltx.
+const MIGRATIONS: &[CommandMigration] = &[
+    // Anomaly 1.5.2 replaced the fullscreen toggle with a three-way mode.
+    CommandMigration {
+        old: "rs_fullscreen",
+        new: Some("rs_screenmode"),
+        values: &[("on", "fullscreen"), ("off", "windowed")],
+    },
+    // OWA is DX11-only; the D3D9/D3D10 renderers are gone.
+    CommandMigration {
+        old: "renderer",
+        new: Some("renderer"),
+        values: &[
+            ("renderer_r1", "renderer_r4"),
+            ("renderer_r2a", "renderer_r4"),
+            ("renderer_r2", "renderer_r4"),
+            ("renderer_r2.5", "renderer_r4"),
+            ("renderer_r3", "renderer_r4"),
+        ],
+    },
+];
+
+const fn spec(
+    name: &'static str,
+    label: &'static str,
+    group: SchemaGroup,
+    kind: ValueKind,
+) -> CommandSpec {
+    CommandSpec {
+        name,
+        label,
+        group,
+        kind,
+    }
+}
+
+static COMMANDS: &[CommandSpec] = &[
+    spec("fov", "Field of View", SchemaGroup::Video, ValueKind::Range { min: 5.0, max: 140.0 }),
+    spec("hud_fov", "HUD FOV", SchemaGroup::Video, ValueKind::Range { min: 0.1, max: 1.0 }),
+    spec("rs_screenmode", "Screen Mode", SchemaGroup::Video, ValueKind::Choice(&["fullscreen", "borderless", "windowed"])),
+    spec("r4_lighting_style", "Lighting Style", SchemaGroup::Video, ValueKind::Choice(&["st_opt_dynamic", "st_opt_static"])),
+    spec("r4_static_lighting_quality", "Static Lighting Quality", SchemaGroup::Video, ValueKind::Choice(&["st_opt_low", "st_opt_medium", "st_opt_high"])),
+    spec("r4_hdr10_on", "HDR", SchemaGroup::Video, ValueKind::Bool { numeric: true }),
+    spec("ai_use_torch_dynamic_lights", "AI Torch", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("rs_v_sync", "V-Sync", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("r__framelimit", "Frame Limit", SchemaGroup::Video, ValueKind::Range { min: 0.0, max: 500.0 }),
+    spec("rs_vis_distance", "View Distance", SchemaGroup::Video, ValueKind::Range { min: 0.4, max: 1.5 }),
+    spec("r__optimize_static_geom", "Static Geometry Optimization", SchemaGroup::Video, ValueKind::Range { min: 0.0, max: 4.0 }),
+    spec("r__optimize_dynamic_geom", "Dynamic Geometry Optimization", SchemaGroup::Video, ValueKind::Range { min: 0.0, max: 4.0 }),
+    spec("r__optimize_shadow_geom", "Shadow Geometry Optimization", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("texture_lod", "Texture LOD", SchemaGroup::Video, ValueKind::Range { min: 0.0, max: 4.0 }),
+    spec("r__geometry_lod", "Geometry LOD", SchemaGroup::Video, ValueKind::Range { min: 0.1, max: 1.5 }),
+    spec("r__tf_mipbias", "Texture Mip Bias", SchemaGroup::Video, ValueKind::Range { min: -0.5, max: 0.5 }),
+    spec("r__tf_contrast", "Texture Contrast", SchemaGroup::Video, ValueKind::Range { min: 0.0, max: 1.0 }),
+    spec("r__tf_aniso", "Anisotropic Filtering", SchemaGroup::Video, ValueKind::Choice(&["1", "4", "8", "16"])),
+    spec("r3_msaa", "MSAA", SchemaGroup::Video, ValueKind::Choice(&["st_opt_off", "2x", "4x", "8x"])),
+    spec("r2_smaa", "SMAA Anti-Aliasing", SchemaGroup::Video, ValueKind::Choice(&["off", "low", "medium", "high", "ultra"])),
+    spec("r3_ssfx_taa", "TAA", SchemaGroup::Video, ValueKind::Bool { numeric: true }),
+    spec("r1_detail_textures", "Detail Textures", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("r2_detail_bump", "Detail Bump Mapping", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("r2_steep_parallax", "Steep Parallax", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("r4_enable_tessellation", "Tessellation", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("r4_material_style", "Material Style", SchemaGroup::Video, ValueKind::Choice(&["st_opt_classic", "st_opt_pbr"])),
+    spec("r__detail_density", "Grass Density", SchemaGroup::Video, ValueKind::Range { min: 0.04, max: 1.0 }),
+    spec("r__detail_radius", "Grass Render Distance", SchemaGroup::Video, ValueKind::Range { min: 50.0, max: 250.0 }),
+    spec("r__detail_height", "Grass Height", SchemaGroup::Video, ValueKind::Range { min: 0.5, max: 2.0 }),
+    spec("r2_slight_fade", "Light Fade Distance", SchemaGroup::Video, ValueKind::Range { min: 0.2, max: 1.0 }),
+    spec("r2_ls_squality", "Light Source Quality", SchemaGroup::Video, ValueKind::Range { min: 0.5, max: 1.0 }),
+    spec("r__actor_shadow", "Player Shadow", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("r2_gloss_factor", "Gloss Factor", SchemaGroup::Video, ValueKind::Range { min: 0.0, max: 10.0 }),
+    spec("r2_sun", "Sun Shadows", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("r2_sun_quality", "Sun Shadow Quality", SchemaGroup::Video, ValueKind::Choice(&["st_opt_low", "st_opt_medium", "st_opt_high", "st_opt_ultra", "st_opt_extreme"])),
+    spec("r2_sunshafts_mode", "Sunshafts Mode", SchemaGroup::Video, ValueKind::Choice(&["off", "volumetric", "screen_space", "combined"])),
+    spec("r2_sunshafts_quality", "Sunshafts Quality", SchemaGroup::Video, ValueKind::Choice(&["st_opt_low", "st_opt_medium", "st_opt_high"])),
+    spec("r2_sunshafts_value", "Sunshafts Intensity", SchemaGroup::Video, ValueKind::Range { min: 0.5, max: 2.0 }),
+    spec("r2_sunshafts_min", "Sunshafts Min Threshold", SchemaGroup::Video, ValueKind::Range { min: 0.0, max: 0.5 }),
+    spec("r2_ssao_mode", "SSAO Mode", SchemaGroup::Video, ValueKind::Choice(&["gtao", "ssdo"])),
+    spec("r2_ssao", "SSAO Quality", SchemaGroup::Video, ValueKind::Choice(&["st_opt_off", "st_opt_low", "st_opt_medium", "st_opt_high"])),
+    spec("r2_volumetric_lights", "Volumetric Lights", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("r4_point_light_shadows", "Point Light Shadows", SchemaGroup::Video, ValueKind::Bool { numeric: true }),
+    spec("r2_soft_particles", "Soft Particles", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("r2_dof_enable", "Depth of Field", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("r2_dof_autofocus", "DOF: Auto-focus", SchemaGroup::Video, ValueKind::Bool { numeric: true }),
+    spec("r2_dof_aim", "DOF: Aim", SchemaGroup::Video, ValueKind::Bool { numeric: true }),
+    spec("r2_dof_reload", "DOF: Reload", SchemaGroup::Video, ValueKind::Bool { numeric: true }),
+    spec("r2_dof_ui", "DOF: Inventory", SchemaGroup::Video, ValueKind::Bool { numeric: true }),
+    spec("r2_dof_dialog", "DOF: Dialog", SchemaGroup::Video, ValueKind::Bool { numeric: true }),
+    spec("r2_dof_focus_speed", "DOF: Focus Speed", SchemaGroup::Video, ValueKind::Range { min: 1.0, max: 20.0 }),
+    spec("r2_dof_coc_power", "Focus Sharpness", SchemaGroup::Video, ValueKind::Range { min: 0.5, max: 5.0 }),
+    spec("r2_dof_max_blur", "Max Blur", SchemaGroup::Video, ValueKind::Range { min: 0.1, max: 1.0 }),
+    spec("r2_dof_peripheral_softness", "Peripheral Blur", SchemaGroup::Video, ValueKind::Range { min: 0.0, max: 0.5 }),
+    spec("r2_mblur_enabled", "Motion Blur", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("r2_mblur", "Motion Blur Amount", SchemaGroup::Video, ValueKind::Range { min: 0.0, max: 1.0 }),
+    spec("r2_soft_water", "Soft Water", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("r3_ssfx_water", "SSR Water", SchemaGroup::Video, ValueKind::Bool { numeric: true }),
+    spec("r3_dynamic_wet_surfaces", "Dynamic Wet Surfaces", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("r3_volumetric_smoke", "Volumetric Smoke", SchemaGroup::Video, ValueKind::Bool { numeric: false }),
+    spec("r3_ssfx_fog", "Volumetric Fog", SchemaGroup::Video, ValueKind::Bool { numeric: true }),
+    spec("r3_ssfx_shadows", "Screenspace Shadows", SchemaGroup::Video, ValueKind::Bool { numeric: true }),
+    spec("r3_gi", "Global Illumination", SchemaGroup::Video, ValueKind::Bool { numeric: true }),
+    spec("r4_hires_rts", "Hi-Res Render Targets", SchemaGroup::Video, ValueKind::Bool { numeric: true }),
+    spec("r3_terrain_quality", "Terrain Quality", SchemaGroup::Video, ValueKind::Choice(&["st_terrain_low", "st_terrain_mid", "st_terrain_high"])),
+    spec("snd_volume_eff", "Master Volume", SchemaGroup::Sound, ValueKind::Range { min: 0.0, max: 1.0 }),
+    spec("snd_volume_music", "Music Volume", SchemaGroup::Sound, ValueKind::Range { min: 0.0, max: 1.0 }),
+    spec("snd_audio_effects_quality", "Audio Effects Quality", SchemaGroup::Sound, ValueKind::Choice(&["st_opt_low", "st_opt_medium", "st_opt_high"])),
+    spec("g_dynamic_music", "Dynamic Music", SchemaGroup::Sound, ValueKind::Bool { numeric: false }),
+    spec("mouse_sens", "Mouse Sensitivity", SchemaGroup::Control, ValueKind::Range { min: 0.001, max: 0.6 }),
+    spec("mouse_sens_aim", "Aim Sensitivity", SchemaGroup::Control, ValueKind::Range { min: 0.5, max: 2.0 }),
+    spec("mouse_invert", "Invert Mouse", SchemaGroup::Control, ValueKind::Bool { numeric: false }),
+    spec("g_crouch_toggle", "Crouch Toggle", SchemaGroup::Control, ValueKind::Bool { numeric: false }),
+    spec("g_walk_toggle", "Walk Toggle", SchemaGroup::Control, ValueKind::Bool { numeric: false }),
+    spec("g_sprint_toggle", "Sprint Toggle", SchemaGroup::Control, ValueKind::Bool { numeric: false }),
+    spec("g_lookout_toggle", "Lean Toggle", SchemaGroup::Control, ValueKind::Bool { numeric: false }),
+    spec("wpn_aim_toggle", "Aim Toggle", SchemaGroup::Control, ValueKind::Bool { numeric: true }),
+    spec("g_multi_item_pickup", "Multi-Item Pickup", SchemaGroup::Control, ValueKind::Bool { numeric: false }),
+    spec("g_simple_pda", "Simple PDA", SchemaGroup::Control, ValueKind::Bool { numeric: false }),
+];
+
+/// All commands known to the schema.
+pub fn all() -> &'static [CommandSpec] {
+    COMMANDS
+}
+
+/// Look up the schema entry for a console command.
+pub fn lookup(command: &str) -> Option<&'static CommandSpec> {
+    COMMANDS.iter().find(|s| s.name == command)
+}
+
+/// Human-readable name for a command, if the schema has one.
+pub fn display_name(command: &str) -> Option<&'static str> {
+    lookup(command).map(|s| s.label)
+}
+
+/// Changing this command makes the compiled shader cache stale.
+pub fn invalidates_shader_cache(command: &str) -> bool {
+    SHADER_CACHE_COMMANDS.contains(&command)
+}
+
+/// Changing this command only takes effect after the game restarts.
+pub fn requires_restart(command: &str) -> bool {
+    invalidates_shader_cache(command) || RESTART_COMMANDS.contains(&command)
+}
+
+impl SchemaGroup {
+    /// Page id used in axr_options paths (`video/basic/...`).
+    pub fn page_id(self) -> &'static str {
+        match self {
+            SchemaGroup::Video => "video",
+            SchemaGroup::Sound => "sound",
+            SchemaGroup::Control => "control",
+        }
+    }
+}
+
+/// Options page a command belongs to: the schema's group, or a guess from the
+/// engine's naming prefixes for commands the schema doesn't list.
+pub fn group_of(command: &str) -> Option<SchemaGroup> {
+    if let Some(spec) = lookup(command) {
+        return Some(spec.group);
+    }
+    const VIDEO: &[&str] = &["r1_", "r2_", "r3_", "r4_", "r__", "rs_", "vid_", "texture_lod"];
+    if VIDEO.iter().any(|p| command.starts_with(p)) || command == "renderer" {
+        Some(SchemaGroup::Video)
+    } else if command.starts_with("snd_") {
+        Some(SchemaGroup::Sound)
+    } else if MULTI_KEY_COMMANDS.contains(&command) || command.starts_with("mouse_") {
+        Some(SchemaGroup::Control)
+    } else {
+        None
+    }
+}
+
+/// Migration entry for a command from an older install, if any.
+pub fn migration(command: &str) -> Option<&'static CommandMigration> {
+    MIGRATIONS.iter().find(|m| m.old == command)
+}
+
+/// Map an old command line to its current form. Returns `None` for dropped commands.
+pub fn migrate<'a>(command: &'a str, value: &'a str) -> Option<(&'a str, &'a str)> {
+    let Some(m) = migration(command) else {
+        return Some((command, value));
+    };
+    let new = m.new?;
+    let value = m
+        .values
+        .iter()
+        .find(|(old, _)| *old == value.trim())
+        .map_or(value, |(_, new)| *new);
+    Some((new, value))
+}
+
+/// Identity of a user.ltx line. Plain commands are keyed by name;
+/// bind-style commands by name plus the bound action (`bind forward`).
+pub fn entry_key(name: &str, value: &str) -> String {
+    if MULTI_KEY_COMMANDS.contains(&name)
+        && let Some(action) = value.split_whitespace().next()
+    {
+        return format!("{} {}", name, action);
+    }
+    name.to_string()
+}
+
+/// Split an entry key back into (command, sub-key) — `("bind", Some("forward"))`.
+pub fn split_entry_key(key: &str) -> (&str, Option<&str>) {
+    match key.split_once(' ') {
+        Some((name, action)) if MULTI_KEY_COMMANDS.contains(&name) => (name, Some(action)),
+        _ => (key, None),
+    }
+}
+
+/// Compare two console values, treating numerically equal values
+/// (`1.` vs `1.000000`) as the same.
+pub fn values_equal(a: &str, b: &str) -> bool {
+    let (a, b) = (a.trim(), b.trim());
+    if a == b {
+        return true;
+    }
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(x), Ok(y)) => x == y,
+        _ => false,
+    }
+}
+
+impl ValueKind {
+    /// Check a raw user.ltx value against this kind.
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        let value = value.trim();
+        match self {
+            ValueKind::Bool { .. } => match value {
+                "on" | "off" | "1" | "0" | "true" | "false" => Ok(()),
+                _ => Err(format!("expected on/off or 1/0, got '{}'", value)),
+            },
+            ValueKind::Range { min, max } => {
+                let n = value
+                    .parse::<f64>()
+                    .map_err(|_| format!("expected a number, got '{}'", value))?;
+                if n < *min || n > *max {
+                    return Err(format!("{} is outside {}..{}", value, min, max));
+                }
+                Ok(())
+            }
+            ValueKind::Choice(options) => {
+                if options.contains(&value) {
+                    Ok(())
+                } else {
+                    Err(format!("'{}' is not one of {}", value, options.join(", ")))
+                }
+            }
+        }
+    }
+}
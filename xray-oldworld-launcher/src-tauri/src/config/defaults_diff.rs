@@ -0,0 +1,121 @@
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::config::console_schema;
+use crate::config::user_ltx::UserLtx;
+
+/// Comparison of the user's user.ltx against the game root's user_default.ltx.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultsDiff {
+    /// Present in both files with different values.
+    pub changed: Vec<DiffEntry>,
+    /// Present in user_default.ltx but not in user.ltx.
+    pub missing: Vec<DiffEntry>,
+    /// Present in user.ltx but not in user_default.ltx.
+    pub extra: Vec<DiffEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffEntry {
+    /// Entry key: the command name, or `bind <action>` for bind-style lines.
+    pub key: String,
+    pub command: String,
+    pub display_name: Option<String>,
+    pub user_value: Option<String>,
+    pub default_value: Option<String>,
+}
+
+/// Collect entries keyed by `console_schema::entry_key`, in file order.
+pub fn keyed_entries(ltx: &UserLtx) -> IndexMap<String, (String, String)> {
+    ltx.commands()
+        .map(|(name, value)| {
+            (
+                console_schema::entry_key(name, value),
+                (name.to_string(), value.to_string()),
+            )
+        })
+        .collect()
+}
+
+fn entry(key: &str, command: &str, user: Option<&str>, default: Option<&str>) -> DiffEntry {
+    DiffEntry {
+        key: key.to_string(),
+        command: command.to_string(),
+        display_name: console_schema::display_name(command).map(|s| s.to_string()),
+        user_value: user.map(|s| s.to_string()),
+        default_value: default.map(|s| s.to_string()),
+    }
+}
+
+/// Compare user.ltx against the defaults. Entries are listed in defaults order,
+/// with extra user-only entries in user.ltx order.
+pub fn diff(user: &UserLtx, defaults: &UserLtx) -> DefaultsDiff {
+    let user_entries = keyed_entries(user);
+    let default_entries = keyed_entries(defaults);
+
+    let mut result = DefaultsDiff {
+        changed: Vec::new(),
+        missing: Vec::new(),
+        extra: Vec::new(),
+    };
+
+    for (key, (command, default_value)) in &default_entries {
+        match user_entries.get(key) {
+            Some((_, user_value)) => {
+                if !console_schema::values_equal(user_value, default_value) {
+                    result.changed.push(entry(
+                        key,
+                        command,
+                        Some(user_value),
+                        Some(default_value),
+                    ));
+                }
+            }
+            None => result
+                .missing
+                .push(entry(key, command, None, Some(default_value))),
+        }
+    }
+
+    for (key, (command, user_value)) in &user_entries {
+        if !default_entries.contains_key(key) {
+            result
+                .extra
+                .push(entry(key, command, Some(user_value), None));
+        }
+    }
+
+    result
+}
+
+/// Reset the given entry keys to their default values. Keys that only exist
+/// in user.ltx are removed. Everything not listed is left untouched.
+/// Returns the keys that were actually modified.
+pub fn reset_keys(user: &mut UserLtx, defaults: &UserLtx, keys: &[String]) -> Vec<String> {
+    let default_entries = keyed_entries(defaults);
+    let user_entries = keyed_entries(user);
+    let mut reset = Vec::new();
+
+    for key in keys {
+        match default_entries.get(key) {
+            Some((_, default_value)) => {
+                let already_default = user_entries
+                    .get(key)
+                    .is_some_and(|(_, v)| console_schema::values_equal(v, default_value));
+                if !already_default {
+                    user.set_entry(key, default_value);
+                    reset.push(key.clone());
+                }
+            }
+            None => {
+                if user.remove_entry(key) {
+                    reset.push(key.clone());
+                }
+            }
+        }
+    }
+
+    reset
+}
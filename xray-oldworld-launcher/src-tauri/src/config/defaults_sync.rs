@@ -0,0 +1,208 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::console_schema;
+use crate::config::defaults_diff::keyed_entries;
+use crate::config::user_ltx::UserLtx;
+use crate::fs_util;
+use crate::logging;
+
+/// Copy of the user_default.ltx the user's user.ltx was last reconciled against.
+/// Stored in appdata next to user.ltx; a content mismatch with the game root's
+/// user_default.ltx means the mod shipped new defaults since then.
+const SNAPSHOT_FILENAME: &str = "user_default.reconciled.ltx";
+
+/// Defaults that changed since the last reconcile, as offered to the user.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultsUpdate {
+    /// Commands new in user_default.ltx and absent from user.ltx.
+    pub added: Vec<DefaultsUpdateEntry>,
+    /// Retuned defaults where user.ltx still holds the old default value.
+    pub changed: Vec<DefaultsUpdateEntry>,
+    /// Retuned defaults the user has customized — left alone unless selected.
+    pub customized: Vec<DefaultsUpdateEntry>,
+    /// True when no snapshot existed, so only new keys could be detected.
+    pub first_reconcile: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultsUpdateEntry {
+    pub key: String,
+    pub command: String,
+    pub display_name: Option<String>,
+    pub user_value: Option<String>,
+    pub old_default: Option<String>,
+    pub new_default: String,
+}
+
+impl DefaultsUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.customized.is_empty()
+    }
+}
+
+fn snapshot_path(appdata_path: &Path) -> PathBuf {
+    appdata_path.join(SNAPSHOT_FILENAME)
+}
+
+/// Check whether user_default.ltx changed since the last reconcile.
+/// Returns `None` when there is nothing to offer: defaults unchanged,
+/// user_default.ltx missing, or no user.ltx yet (the fallback already shows defaults).
+pub fn check(appdata_path: &Path, game_root: &Path) -> Result<Option<DefaultsUpdate>, String> {
+    let defaults_path = UserLtx::defaults_path(game_root);
+    if !defaults_path.exists() || !appdata_path.join("user.ltx").exists() {
+        return Ok(None);
+    }
+
+    let current = fs::read_to_string(&defaults_path)
+        .map_err(|e| format!("Failed to read {:?}: {}", defaults_path, e))?;
+    let snapshot = fs::read_to_string(snapshot_path(appdata_path)).ok();
+
+    if snapshot.as_deref() == Some(current.as_str()) {
+        return Ok(None);
+    }
+
+    let user = UserLtx::load(appdata_path);
+    let old_defaults = snapshot.as_deref().map(UserLtx::parse);
+    let update = classify(&user, &UserLtx::parse(&current), old_defaults.as_ref());
+
+    if update.is_empty() {
+        // Nothing the user needs to decide on — just mark these defaults as seen
+        record_snapshot(appdata_path, game_root)?;
+        return Ok(None);
+    }
+
+    logging::log(format!(
+        "OWA: user_default.ltx changed since last reconcile: {} added, {} changed, {} customized",
+        update.added.len(),
+        update.changed.len(),
+        update.customized.len()
+    ));
+
+    Ok(Some(update))
+}
+
+/// Sort the keys of `new_defaults` into added, changed and customized by
+/// comparing user.ltx against the defaults it was last reconciled with.
+fn classify(
+    user: &UserLtx,
+    new_defaults: &UserLtx,
+    old_defaults: Option<&UserLtx>,
+) -> DefaultsUpdate {
+    let user = keyed_entries(user);
+    let new_defaults = keyed_entries(new_defaults);
+    let old_defaults = old_defaults.map(keyed_entries);
+
+    let mut update = DefaultsUpdate {
+        added: Vec::new(),
+        changed: Vec::new(),
+        customized: Vec::new(),
+        first_reconcile: old_defaults.is_none(),
+    };
+
+    for (key, (command, new_default)) in &new_defaults {
+        let user_value = user.get(key).map(|(_, v)| v.clone());
+        let old_default = old_defaults
+            .as_ref()
+            .and_then(|old| old.get(key))
+            .map(|(_, v)| v.clone());
+
+        let entry = DefaultsUpdateEntry {
+            key: key.clone(),
+            command: command.clone(),
+            display_name: console_schema::display_name(command).map(|s| s.to_string()),
+            user_value: user_value.clone(),
+            old_default: old_default.clone(),
+            new_default: new_default.clone(),
+        };
+
+        match (&user_value, &old_default) {
+            (None, _) => update.added.push(entry),
+            (Some(user_value), Some(old_default)) => {
+                if console_schema::values_equal(old_default, new_default)
+                    || console_schema::values_equal(user_value, new_default)
+                {
+                    continue;
+                }
+                if console_schema::values_equal(user_value, old_default) {
+                    update.changed.push(entry);
+                } else {
+                    update.customized.push(entry);
+                }
+            }
+            // No snapshot: can't tell a retuned default from a user customization
+            (Some(_), None) => {}
+        }
+    }
+    update
+}
+
+/// Merge the selected keys from the current user_default.ltx into user.ltx
+/// and record the defaults as reconciled. An empty `keys` list just records
+/// the snapshot, dismissing the update. Keys the user customized, or whose
+/// history is unknown, are skipped unless `force` is set. Returns the keys
+/// written.
+pub fn apply(
+    appdata_path: &Path,
+    game_root: &Path,
+    keys: &[String],
+    force: bool,
+) -> Result<Vec<String>, String> {
+    let defaults = UserLtx::load_defaults(game_root);
+    let default_entries = keyed_entries(&defaults);
+
+    let mut applied = Vec::new();
+    if !keys.is_empty() {
+        let mut user = UserLtx::load(appdata_path);
+        // Classified again here: the offer may be stale, and a key the user
+        // customized since must not be overwritten by accident.
+        let snapshot = fs::read_to_string(snapshot_path(appdata_path)).ok();
+        let old_defaults = snapshot.as_deref().map(UserLtx::parse);
+        let update = classify(&user, &defaults, old_defaults.as_ref());
+        let safe: HashSet<&str> = update
+            .added
+            .iter()
+            .chain(&update.changed)
+            .map(|entry| entry.key.as_str())
+            .collect();
+
+        for key in keys {
+            if !force && !safe.contains(key.as_str()) {
+                logging::log(format!("OWA: Kept customized {} (not forced)", key));
+                continue;
+            }
+            if let Some((_, value)) = default_entries.get(key) {
+                user.set_entry(key, value);
+                applied.push(key.clone());
+            }
+        }
+        if !applied.is_empty() {
+            user.save(appdata_path)?;
+        }
+    }
+
+    record_snapshot(appdata_path, game_root)?;
+    logging::log(format!(
+        "OWA: Merged {} new defaults into user.ltx: {:?}",
+        applied.len(),
+        applied
+    ));
+    Ok(applied)
+}
+
+/// Record the current user_default.ltx as reconciled.
+pub fn record_snapshot(appdata_path: &Path, game_root: &Path) -> Result<(), String> {
+    let defaults_path = UserLtx::defaults_path(game_root);
+    if !defaults_path.exists() {
+        return Ok(());
+    }
+    let content = fs::read(&defaults_path)
+        .map_err(|e| format!("Failed to read {:?}: {}", defaults_path, e))?;
+    fs_util::write_atomic(&snapshot_path(appdata_path), content, false)
+        .map_err(|e| format!("Failed to record defaults snapshot: {}", e))
+}
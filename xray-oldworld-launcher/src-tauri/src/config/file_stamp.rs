@@ -0,0 +1,48 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::time::SystemTime;
+
+/// A file's modification time and content hash at the moment it was read.
+/// Used to notice when something else — usually the game on exit —
+/// rewrote the file behind the launcher's back.
+#[derive(Debug, Clone)]
+pub struct FileStamp {
+    pub mtime: Option<SystemTime>,
+    pub hash: u64,
+}
+
+impl FileStamp {
+    /// Read a file and stamp it. Returns `None` if it doesn't exist or can't be read.
+    pub fn read(path: &Path) -> Option<Self> {
+        let bytes = fs::read(path).ok()?;
+        Some(FileStamp {
+            mtime: modified(path),
+            hash: hash_bytes(&bytes),
+        })
+    }
+
+    /// Whether the file at `path` no longer matches this stamp. An unchanged
+    /// mtime answers without reading the file; otherwise the content hash
+    /// decides, so a rewrite with identical contents doesn't count.
+    /// A file that is gone or unreadable counts as unchanged.
+    pub fn changed(&self, path: &Path) -> bool {
+        let mtime = modified(path);
+        if mtime.is_some() && mtime == self.mtime {
+            return false;
+        }
+        FileStamp::read(path).is_some_and(|now| now.hash != self.hash)
+    }
+}
+
+/// Modification time of a file, if available.
+pub fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
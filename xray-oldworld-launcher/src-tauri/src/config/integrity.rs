@@ -0,0 +1,294 @@
+use serde::Serialize;
+
+use crate::config::console_schema;
+use crate::config::defaults_diff::keyed_entries;
+use crate::config::user_ltx::UserLtx;
+
+/// Fewer commands than this fraction of user_default.ltx suggests the file
+/// was cut short. The game writes every registered command on exit.
+const MIN_COMMAND_RATIO: f64 = 0.5;
+
+/// Problems found in a user.ltx, typically after a crash or power loss mid-write.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub issues: Vec<IntegrityIssue>,
+    pub command_count: usize,
+    /// Commands in user_default.ltx, for comparison. 0 if it's missing.
+    pub default_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueKind {
+    /// NUL bytes, control characters or invalid UTF-8.
+    BinaryContent,
+    /// The last line has no newline and doesn't look complete.
+    TruncatedLastLine,
+    /// A command name that can't be a console command.
+    GarbledLine,
+    MissingValue,
+    PresetMissing,
+    TooFewCommands,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityIssue {
+    pub kind: IssueKind,
+    /// 1-based line number, when the issue is tied to a line.
+    pub line: Option<usize>,
+    pub detail: String,
+}
+
+/// What `repair` did.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairReport {
+    /// Lines carried over from the damaged file.
+    pub kept: usize,
+    pub dropped: Vec<DroppedLine>,
+    /// Entry keys filled in from user_default.ltx.
+    pub filled: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedLine {
+    pub line: usize,
+    pub text: String,
+    pub reason: IssueKind,
+}
+
+impl IntegrityReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Check raw user.ltx bytes against `defaults` (user_default.ltx).
+pub fn check(bytes: &[u8], defaults: &UserLtx) -> IntegrityReport {
+    let mut issues = Vec::new();
+
+    if std::str::from_utf8(bytes).is_err() {
+        issues.push(IntegrityIssue {
+            kind: IssueKind::BinaryContent,
+            line: None,
+            detail: "file is not valid UTF-8 text".to_string(),
+        });
+    }
+
+    let lines = split_lines(bytes);
+    let mut command_count = 0;
+    let mut has_preset = false;
+    for (idx, line) in lines.iter().enumerate() {
+        let number = idx + 1;
+        let is_last = number == lines.len();
+        match check_line(line, is_last && !ends_with_newline(bytes), defaults) {
+            LineCheck::Skip => {}
+            LineCheck::Command(name) => {
+                command_count += 1;
+                has_preset |= name == "_preset";
+            }
+            LineCheck::Bad(kind, detail) => {
+                if kind == IssueKind::MissingValue {
+                    command_count += 1;
+                }
+                issues.push(IntegrityIssue {
+                    kind,
+                    line: Some(number),
+                    detail,
+                });
+            }
+        }
+    }
+
+    let default_count = defaults.commands().count();
+    if !has_preset && default_count > 0 {
+        issues.push(IntegrityIssue {
+            kind: IssueKind::PresetMissing,
+            line: None,
+            detail: "the `_preset` line is missing".to_string(),
+        });
+    }
+    if default_count > 0 && (command_count as f64) < default_count as f64 * MIN_COMMAND_RATIO {
+        issues.push(IntegrityIssue {
+            kind: IssueKind::TooFewCommands,
+            line: None,
+            detail: format!(
+                "only {} commands, user_default.ltx has {}",
+                command_count, default_count
+            ),
+        });
+    }
+
+    IntegrityReport {
+        issues,
+        command_count,
+        default_count,
+    }
+}
+
+/// Rebuild user.ltx from a damaged file: start from the defaults, then apply
+/// every line that passes the same checks as `check`. Lines the defaults
+/// don't have are appended in their original order.
+pub fn repair(bytes: &[u8], defaults: &UserLtx) -> (UserLtx, RepairReport) {
+    let lines = split_lines(bytes);
+    let mut salvaged = Vec::new();
+    let mut dropped = Vec::new();
+
+    for (idx, line) in lines.iter().enumerate() {
+        let is_last = idx + 1 == lines.len();
+        match check_line(line, is_last && !ends_with_newline(bytes), defaults) {
+            LineCheck::Skip => {}
+            LineCheck::Command(_) => salvaged.push(line.trim().to_string()),
+            LineCheck::Bad(reason, _) => dropped.push(DroppedLine {
+                line: idx + 1,
+                text: printable(line),
+                reason,
+            }),
+        }
+    }
+
+    let salvaged = UserLtx::parse(&salvaged.join("\n"));
+    let salvaged_entries = keyed_entries(&salvaged);
+    let mut repaired = defaults.clone();
+    for (key, (_, value)) in &salvaged_entries {
+        repaired.set_entry(key, value);
+    }
+    let filled = keyed_entries(defaults)
+        .into_keys()
+        .filter(|key| !salvaged_entries.contains_key(key))
+        .collect();
+
+    let report = RepairReport {
+        kept: salvaged_entries.len(),
+        dropped,
+        filled,
+    };
+    (repaired, report)
+}
+
+enum LineCheck<'a> {
+    /// Blank or comment.
+    Skip,
+    Command(&'a str),
+    Bad(IssueKind, String),
+}
+
+fn check_line<'a>(line: &'a str, unterminated: bool, defaults: &UserLtx) -> LineCheck<'a> {
+    let trimmed = line.trim().trim_start_matches('\u{FEFF}');
+    if trimmed.is_empty() || trimmed.starts_with(';') || trimmed.starts_with("//") {
+        return LineCheck::Skip;
+    }
+
+    if trimmed
+        .chars()
+        .any(|c| c == '\u{FFFD}' || (c.is_control() && c != '\t'))
+    {
+        return LineCheck::Bad(
+            IssueKind::BinaryContent,
+            format!("binary data: {}", printable(trimmed)),
+        );
+    }
+
+    let (name, value) = match trimmed.split_once(char::is_whitespace) {
+        Some((name, value)) => (name, value.trim()),
+        None => (trimmed, ""),
+    };
+
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return LineCheck::Bad(IssueKind::GarbledLine, format!("'{}' is not a command", name));
+    }
+
+    // A half-written last line usually still has a plausible name, so it
+    // must look like a complete line the defaults would recognise.
+    let valid_value = |value: &str| {
+        console_schema::lookup(name).is_none_or(|spec| spec.kind.validate(value).is_ok())
+    };
+    // Some commands take no value (`default_controls`); only flag a missing
+    // one when the schema or the defaults say it needs a value.
+    let missing_value = value.is_empty() && requires_value(name, defaults);
+    if unterminated && (missing_value || !defaults.has(name) || !valid_value(value)) {
+        return LineCheck::Bad(
+            IssueKind::TruncatedLastLine,
+            format!("last line looks cut off: '{}'", trimmed),
+        );
+    }
+
+    if missing_value {
+        return LineCheck::Bad(IssueKind::MissingValue, format!("'{}' has no value", name));
+    }
+
+    LineCheck::Command(name)
+}
+
+fn requires_value(name: &str, defaults: &UserLtx) -> bool {
+    console_schema::lookup(name).is_some()
+        || defaults.get(name).is_some_and(|v| !v.trim().is_empty())
+}
+
+fn split_lines(bytes: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(bytes)
+        .lines()
+        .map(|l| l.to_string())
+        .collect()
+}
+
+fn ends_with_newline(bytes: &[u8]) -> bool {
+    bytes.is_empty() || bytes.ends_with(b"\n")
+}
+
+/// A line made safe to show in the UI.
+fn printable(line: &str) -> String {
+    line.chars()
+        .map(|c| if c.is_control() && c != '\t' { '\u{FFFD}' } else { c })
+        .take(80)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHIPPED: &[&str] = &[
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../user_default.ltx")),
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../dev-gameroot/user_default.ltx")),
+    ];
+
+    #[test]
+    fn shipped_defaults_are_ok() {
+        for content in SHIPPED {
+            let defaults = UserLtx::parse(content);
+            let report = check(content.as_bytes(), &defaults);
+            assert!(report.is_ok(), "{:?}", report.issues);
+            let (_, repair) = repair(content.as_bytes(), &defaults);
+            assert!(repair.dropped.is_empty(), "{:?}", repair.dropped);
+        }
+    }
+
+    #[test]
+    fn seeded_user_ltx_is_ok() {
+        for content in SHIPPED {
+            let defaults = UserLtx::parse(content);
+            // What provisioning writes, and what the launcher writes back after a save
+            let rendered = UserLtx::parse(content).render();
+            for seeded in [*content, rendered.as_str()] {
+                let report = check(seeded.as_bytes(), &defaults);
+                assert!(report.is_ok(), "{:?}", report.issues);
+            }
+        }
+    }
+
+    #[test]
+    fn missing_required_value_is_flagged() {
+        let defaults = UserLtx::parse("_preset Default\nmouse_sens 0.12\ndefault_controls\n");
+        let report = check(b"_preset Default\nmouse_sens\ndefault_controls\n", &defaults);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].kind, IssueKind::MissingValue);
+        assert_eq!(report.issues[0].line, Some(2));
+    }
+}
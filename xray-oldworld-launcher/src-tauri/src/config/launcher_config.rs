@@ -1,11 +1,29 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-/// Launcher-specific configuration stored as JSON next to the launcher executable.
+use crate::fs_util;
+use crate::logging;
+
+/// Current launcher_config.json layout. Bump it and append to `MIGRATIONS`
+/// when a field is renamed or restructured.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Remembers the newest schema the user was already told about, so a
+/// config from a newer launcher is mentioned once rather than every start.
+const SCHEMA_NOTICE_FILENAME: &str = "launcher_config.schema_notice";
+
+/// `MIGRATIONS[n]` upgrades a version-`n` file to version `n + 1`.
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[migrate_v0];
+
+/// Launcher-specific configuration. launcher_config.json holds only the fields
+/// the user set; the effective values come from `config_layers`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LauncherConfig {
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
     #[serde(default)]
     pub use_avx: bool,
     #[serde(default)]
@@ -20,6 +38,61 @@ pub struct LauncherConfig {
     pub language: String,
     #[serde(default)]
     pub linux_custom_command: Option<String>,
+    /// Validate and launch on startup, showing the window only if that fails.
+    #[serde(default)]
+    pub auto_launch: bool,
+    /// Named installs to switch between. Managed by the installation
+    /// commands; saves from the UI never change it.
+    #[serde(default)]
+    pub installations: Vec<Installation>,
+    /// Name of the selected entry in `installations`, if any.
+    #[serde(default)]
+    pub active_installation: Option<String>,
+    /// Fields this version doesn't know (UI-only settings, newer launchers),
+    /// carried through so a save doesn't drop them.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A named game install with its own launch settings. Selecting it copies
+/// these onto the user's top-level fields; edits made while it's selected are
+/// copied back. Only settings the user set are stored: the rest come from the
+/// defaults of whichever mod the install holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Installation {
+    pub name: String,
+    pub game_root: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_avx: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_args: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub linux_custom_command: Option<String>,
+}
+
+/// Problems found loading launcher_config.json, for the UI to show.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigRecovery {
+    pub problems: Vec<ConfigProblem>,
+    /// Copy of the original file, kept before anything overwrites it.
+    pub corrupt_copy: Option<String>,
+    /// Schema of a file written by a newer launcher, when the user hasn't
+    /// been told about it yet. Not a problem: unknown fields are kept.
+    pub newer_schema: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigProblem {
+    /// The field that was reset to its default; `None` for whole-file problems.
+    pub field: Option<String>,
+    pub message: String,
+}
+
+fn default_schema_version() -> u32 {
+    SCHEMA_VERSION
 }
 
 fn default_shadow_map_size() -> u32 {
@@ -33,6 +106,7 @@ fn default_language() -> String {
 impl Default for LauncherConfig {
     fn default() -> Self {
         LauncherConfig {
+            schema_version: SCHEMA_VERSION,
             use_avx: false,
             debug_mode: false,
             shadow_map_size: 2048,
@@ -40,6 +114,10 @@ impl Default for LauncherConfig {
             game_root: None,
             language: default_language(),
             linux_custom_command: None,
+            auto_launch: false,
+            installations: Vec::new(),
+            active_installation: None,
+            extra: Map::new(),
         }
     }
 }
@@ -47,24 +125,271 @@ impl Default for LauncherConfig {
 impl LauncherConfig {
     const FILENAME: &'static str = "launcher_config.json";
 
-    /// Load config from the launcher directory. Returns default if file doesn't exist.
-    pub fn load(launcher_dir: &Path) -> Self {
-        let path = launcher_dir.join(Self::FILENAME);
+    /// Load the fields set in `config_dir` (see `storage`). Empty if the file
+    /// doesn't exist.
+    ///
+    /// Older layouts are migrated and bad values are recovered field by field:
+    /// a field that doesn't parse is dropped (so it falls back to the layers
+    /// below) while every valid field is kept. If anything had to be dropped,
+    /// the original file is copied to `launcher_config.json.corrupt` and the
+    /// problems are returned. A file from a newer launcher isn't damaged; it
+    /// is reported once through `newer_schema`.
+    pub fn load_checked(config_dir: &Path) -> (Map<String, Value>, Option<ConfigRecovery>) {
+        let path = Self::file_path(config_dir);
         if !path.exists() {
-            return Self::default();
+            return (Map::new(), None);
+        }
+
+        let content = match fs::read(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                logging::log(format!("ERROR reading launcher config: {}", e));
+                return (Map::new(), None);
+            }
+        };
+
+        let (fields, problems, newer) = Self::parse_lenient(&content);
+        let newer_schema = newer.filter(|&version| Self::first_schema_notice(config_dir, version));
+        if problems.is_empty() {
+            let recovery = newer_schema.map(|_| ConfigRecovery {
+                problems,
+                corrupt_copy: None,
+                newer_schema,
+            });
+            return (fields, recovery);
+        }
+
+        logging::log(format!(
+            "WARNING: launcher_config.json had {} problem(s): {:?}",
+            problems.len(),
+            problems
+        ));
+        let corrupt = corrupt_path(&path);
+        let corrupt_copy = match fs_util::write_atomic(&corrupt, &content, false) {
+            Ok(()) => Some(corrupt.display().to_string()),
+            Err(e) => {
+                logging::log(format!("ERROR preserving corrupt launcher config: {}", e));
+                None
+            }
+        };
+
+        (
+            fields,
+            Some(ConfigRecovery {
+                problems,
+                corrupt_copy,
+                newer_schema,
+            }),
+        )
+    }
+
+    /// Whether `version` is newer than any schema already noticed, recording
+    /// it so the notice isn't repeated.
+    fn first_schema_notice(config_dir: &Path, version: u32) -> bool {
+        let path = config_dir.join(SCHEMA_NOTICE_FILENAME);
+        let noticed = fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| s.trim().parse::<u32>().ok());
+        if noticed.is_some_and(|noticed| noticed >= version) {
+            return false;
+        }
+        if let Err(e) = fs_util::write_atomic(&path, version.to_string(), false) {
+            logging::log(format!("ERROR recording schema notice: {}", e));
+        }
+        true
+    }
+
+    /// Fields, problems, and the file's schema if it's newer than this one.
+    fn parse_lenient(content: &[u8]) -> (Map<String, Value>, Vec<ConfigProblem>, Option<u32>) {
+        let whole_file = |message: String| {
+            vec![ConfigProblem {
+                field: None,
+                message,
+            }]
+        };
+
+        let mut fields = match serde_json::from_slice::<Value>(content) {
+            Ok(Value::Object(fields)) => fields,
+            Ok(_) => return (Map::new(), whole_file("not a JSON object".to_string()), None),
+            Err(e) => return (Map::new(), whole_file(format!("not valid JSON: {}", e)), None),
+        };
+
+        let mut problems = Vec::new();
+        let version = match fields.get("schemaVersion") {
+            None => 0,
+            Some(v) => match v.as_u64().and_then(|v| u32::try_from(v).ok()) {
+                Some(v) => v,
+                None => {
+                    problems.push(ConfigProblem {
+                        field: Some("schemaVersion".to_string()),
+                        message: format!("invalid schema version {}", v),
+                    });
+                    0
+                }
+            },
+        };
+        let newer = (version > SCHEMA_VERSION).then_some(version);
+        if newer.is_some() {
+            logging::log(format!(
+                "launcher_config.json is from a newer launcher (schema {}, this one knows {})",
+                version, SCHEMA_VERSION
+            ));
         }
-        match fs::read_to_string(&path) {
-            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
-            Err(_) => Self::default(),
+        for migrate in MIGRATIONS.iter().skip(version as usize) {
+            migrate(&mut fields);
         }
+        if version < SCHEMA_VERSION {
+            logging::log(format!(
+                "Migrated launcher config from schema {} to {}",
+                version, SCHEMA_VERSION
+            ));
+        }
+        fields.insert("schemaVersion".to_string(), Value::from(SCHEMA_VERSION.max(version)));
+
+        let (accepted, rejected) = Self::accept_fields(fields);
+        problems.extend(rejected);
+        (accepted, problems, newer)
+    }
+
+    /// Split `fields` into those that parse and a problem for each that
+    /// doesn't. Each field is tried on its own against the defaults, so one
+    /// bad value never takes valid ones down with it.
+    pub fn accept_fields(fields: Map<String, Value>) -> (Map<String, Value>, Vec<ConfigProblem>) {
+        let defaults = Self::default_fields();
+        let mut accepted = Map::new();
+        let mut problems = Vec::new();
+        for (key, value) in fields {
+            let mut candidate = defaults.clone();
+            candidate.insert(key.clone(), value.clone());
+            match serde_json::from_value::<Self>(Value::Object(candidate)) {
+                Ok(_) => {
+                    accepted.insert(key, value);
+                }
+                Err(e) => problems.push(ConfigProblem {
+                    field: Some(key),
+                    message: format!("{}; using the default", e),
+                }),
+            }
+        }
+        (accepted, problems)
     }
 
-    /// Save config to the launcher directory.
-    pub fn save(&self, launcher_dir: &Path) -> Result<(), String> {
-        let path = launcher_dir.join(Self::FILENAME);
-        let content = serde_json::to_string_pretty(self)
-            .map_err(|e| format!("Failed to serialize launcher config: {}", e))?;
-        fs::write(&path, content)
-            .map_err(|e| format!("Failed to write launcher config to {:?}: {}", path, e))
+    /// The built-in defaults as JSON fields.
+    pub fn default_fields() -> Map<String, Value> {
+        match serde_json::to_value(Self::default()) {
+            Ok(Value::Object(map)) => map,
+            _ => Map::new(),
+        }
+    }
+
+    /// Build a config from merged fields; anything missing takes its default.
+    pub fn from_fields(fields: Map<String, Value>) -> Self {
+        serde_json::from_value(Value::Object(fields)).unwrap_or_default()
+    }
+
+    /// Save the user's fields to `config_dir`.
+    pub fn save_fields(config_dir: &Path, fields: &Map<String, Value>) -> Result<(), String> {
+        let path = Self::file_path(config_dir);
+        fs_util::write_atomic(&path, Self::fields_to_json(fields)?, false)
+            .map_err(|e| format!("Failed to write launcher config: {}", e))
+    }
+
+    /// Serialize the user's fields to the on-disk JSON format.
+    pub fn fields_to_json(fields: &Map<String, Value>) -> Result<String, String> {
+        serde_json::to_string_pretty(fields)
+            .map_err(|e| format!("Failed to serialize launcher config: {}", e))
+    }
+
+    pub fn file_path(config_dir: &Path) -> PathBuf {
+        config_dir.join(Self::FILENAME)
+    }
+
+    pub fn installation(&self, name: &str) -> Option<&Installation> {
+        self.installations.iter().find(|i| i.name == name)
+    }
+
+    /// Make `name` the active installation in the user's `fields`: its game
+    /// root and the settings it sets replace the top-level ones, and settings
+    /// it leaves unset are removed so the layers below apply.
+    pub fn select_installation(fields: &mut Map<String, Value>, name: &str) -> Result<(), String> {
+        let entry = installations_in(fields)
+            .into_iter()
+            .find(|i| i.name == name)
+            .ok_or_else(|| format!("No installation named '{}'", name))?;
+        fields.insert("gameRoot".to_string(), Value::String(entry.game_root.clone()));
+        for (key, value) in installation_settings(&entry) {
+            match value {
+                Some(value) => fields.insert(key.to_string(), value),
+                None => fields.remove(key),
+            };
+        }
+        fields.insert("activeInstallation".to_string(), Value::String(entry.name));
+        Ok(())
+    }
+
+    /// Copy the per-install settings in the user's `fields` into the active
+    /// entry. Only the user layer is copied, never overrides or mod defaults.
+    pub fn sync_active_installation(fields: &mut Map<String, Value>) {
+        let Some(name) = fields.get("activeInstallation").and_then(Value::as_str) else {
+            return;
+        };
+        let name = name.to_string();
+        let mut installations = installations_in(fields);
+        let Some(entry) = installations.iter_mut().find(|i| i.name == name) else {
+            return;
+        };
+        if let Some(root) = fields.get("gameRoot").and_then(Value::as_str) {
+            entry.game_root = root.to_string();
+        }
+        let string = |key: &str| fields.get(key).and_then(Value::as_str).map(str::to_string);
+        entry.use_avx = fields.get("useAvx").and_then(Value::as_bool);
+        entry.custom_args = string("customArgs");
+        entry.linux_custom_command = string("linuxCustomCommand");
+        if let Ok(value) = serde_json::to_value(&installations) {
+            fields.insert("installations".to_string(), value);
+        }
+    }
+}
+
+fn installations_in(fields: &Map<String, Value>) -> Vec<Installation> {
+    fields
+        .get("installations")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// An installation's settings as launcher_config.json fields; `None` if unset.
+fn installation_settings(entry: &Installation) -> [(&'static str, Option<Value>); 3] {
+    [
+        ("useAvx", entry.use_avx.map(Value::Bool)),
+        ("customArgs", entry.custom_args.clone().map(Value::String)),
+        (
+            "linuxCustomCommand",
+            entry.linux_custom_command.clone().map(Value::String),
+        ),
+    ]
+}
+
+fn corrupt_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".corrupt");
+    path.with_file_name(name)
+}
+
+/// Unversioned files: accept snake_case keys from hand-edited configs, which
+/// serde would otherwise ignore and silently reset.
+fn migrate_v0(fields: &mut Map<String, Value>) {
+    const RENAMES: &[(&str, &str)] = &[
+        ("use_avx", "useAvx"),
+        ("debug_mode", "debugMode"),
+        ("shadow_map_size", "shadowMapSize"),
+        ("custom_args", "customArgs"),
+        ("game_root", "gameRoot"),
+        ("linux_custom_command", "linuxCustomCommand"),
+    ];
+    for (old, new) in RENAMES {
+        if let Some(value) = fields.remove(*old) {
+            fields.entry(*new).or_insert(value);
+        }
     }
 }
@@ -2,6 +2,8 @@ use indexmap::IndexMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::fs_util;
+
 /// Represents a parsed LTX file (INI-like format used by S.T.A.L.K.E.R.)
 #[derive(Debug, Clone)]
 pub struct LtxFile {
@@ -246,12 +248,11 @@ impl LtxFile {
     /// Preserves section ordering. Uses consistent formatting.
     pub fn write(&self, path: &Path) -> Result<(), LtxError> {
         let content = self.to_string_formatted();
-        fs::write(path, content)
-            .map_err(|e| LtxError::Io(format!("Failed to write {:?}: {}", path, e)))
+        fs_util::write_atomic(path, content, true).map_err(LtxError::Io)
     }
 
     /// Format the LTX file as a string with aligned key-value pairs.
-    fn to_string_formatted(&self) -> String {
+    pub fn to_string_formatted(&self) -> String {
         let mut output = String::new();
 
         // Root entries (rare, but handle them)
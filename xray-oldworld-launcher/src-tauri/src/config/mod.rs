@@ -1,3 +1,18 @@
+pub mod axr_options;
+pub mod change_journal;
+pub mod command_scan;
+pub mod config_layers;
+pub mod console_schema;
+pub mod defaults_diff;
+pub mod defaults_sync;
+pub mod file_stamp;
+pub mod integrity;
 pub mod launcher_config;
 pub mod ltx_parser;
+pub mod options_view;
+pub mod save_merge;
+pub mod save_preview;
+pub mod settings_import;
+pub mod settings_share;
+pub mod transaction;
 pub mod user_ltx;
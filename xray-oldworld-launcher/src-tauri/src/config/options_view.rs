@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::config::console_schema;
+use crate::config::defaults_diff::keyed_entries;
+use crate::config::user_ltx::{LineView, UserLtx};
+
+/// Where a command's current value comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValueSource {
+    /// Set in user.ltx.
+    User,
+    /// Not in user.ltx (or no user.ltx yet); shown from user_default.ltx.
+    Default,
+    /// Known to the schema but in neither file.
+    Missing,
+}
+
+/// One command as presented to the UI.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionEntry {
+    /// Entry key: the command name, or `bind <action>` for bind-style lines.
+    pub key: String,
+    pub command: String,
+    pub display_name: Option<String>,
+    pub value: Option<String>,
+    pub default_value: Option<String>,
+    pub source: ValueSource,
+    /// Value differs from user_default.ltx.
+    pub modified: bool,
+    /// Value passes the console schema (always true for commands it doesn't know).
+    pub valid: bool,
+    pub validation_error: Option<String>,
+    /// 1-based line in user.ltx, for entries that come from it.
+    pub line: Option<usize>,
+    /// The same key already appeared on an earlier line; the game applies the last one.
+    pub duplicate: bool,
+}
+
+/// A comment line in user.ltx.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentLine {
+    pub line: usize,
+    pub text: String,
+}
+
+fn validate(command: &str, value: Option<&str>) -> (bool, Option<String>) {
+    match (console_schema::lookup(command), value) {
+        (Some(spec), Some(value)) => match spec.kind.validate(value) {
+            Ok(()) => (true, None),
+            Err(e) => (false, Some(e)),
+        },
+        _ => (true, None),
+    }
+}
+
+fn entry(
+    key: String,
+    command: &str,
+    value: Option<&str>,
+    default_value: Option<&str>,
+    source: ValueSource,
+    line: Option<usize>,
+    duplicate: bool,
+) -> OptionEntry {
+    let (valid, validation_error) = validate(command, value);
+    let modified = match (value, default_value) {
+        (Some(v), Some(d)) => !console_schema::values_equal(v, d),
+        (Some(_), None) => true,
+        _ => false,
+    };
+    OptionEntry {
+        key,
+        command: command.to_string(),
+        display_name: console_schema::display_name(command).map(|s| s.to_string()),
+        value: value.map(|s| s.to_string()),
+        default_value: default_value.map(|s| s.to_string()),
+        source,
+        modified,
+        valid,
+        validation_error,
+        line,
+        duplicate,
+    }
+}
+
+/// Build the ordered entry list: user.ltx lines first, then commands only in
+/// user_default.ltx, then schema commands found in neither file.
+pub fn build(user: Option<&UserLtx>, defaults: &UserLtx) -> (Vec<OptionEntry>, Vec<CommentLine>) {
+    let default_entries = keyed_entries(defaults);
+    let mut entries = Vec::new();
+    let mut comments = Vec::new();
+    let mut seen = HashSet::new();
+
+    if let Some(user) = user {
+        for (i, line) in user.lines().enumerate() {
+            match line {
+                LineView::Command { name, value } => {
+                    let key = console_schema::entry_key(name, value);
+                    let default_value = default_entries.get(&key).map(|(_, v)| v.as_str());
+                    let duplicate = !seen.insert(key.clone());
+                    entries.push(entry(
+                        key,
+                        name,
+                        Some(value),
+                        default_value,
+                        ValueSource::User,
+                        Some(i + 1),
+                        duplicate,
+                    ));
+                }
+                LineView::Comment(text) => comments.push(CommentLine {
+                    line: i + 1,
+                    text: text.to_string(),
+                }),
+                LineView::Empty => {}
+            }
+        }
+    }
+
+    for (key, (command, value)) in &default_entries {
+        if seen.insert(key.clone()) {
+            entries.push(entry(
+                key.clone(),
+                command,
+                Some(value),
+                Some(value),
+                ValueSource::Default,
+                None,
+                false,
+            ));
+        }
+    }
+
+    for spec in console_schema::all() {
+        if seen.insert(spec.name.to_string()) {
+            entries.push(entry(
+                spec.name.to_string(),
+                spec.name,
+                None,
+                None,
+                ValueSource::Missing,
+                None,
+                false,
+            ));
+        }
+    }
+
+    (entries, comments)
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::config::console_schema;
+use crate::config::file_stamp::FileStamp;
+use crate::config::user_ltx::UserLtx;
+
+/// What the UI was shown by the last `get_options`: the user.ltx stamp
+/// (None if the file didn't exist) and the values it displayed.
+#[derive(Debug, Clone)]
+pub struct OptionsBase {
+    pub stamp: Option<FileStamp>,
+    pub values: HashMap<String, String>,
+}
+
+impl OptionsBase {
+    /// Whether user.ltx on disk changed since this base was recorded.
+    pub fn changed_on_disk(&self, path: &Path) -> bool {
+        match &self.stamp {
+            Some(base) => base.changed(path),
+            None => path.exists(),
+        }
+    }
+}
+
+/// A pending change to a command that was also changed on disk.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveConflict {
+    pub command: String,
+    pub display_name: Option<String>,
+    /// Value the UI loaded.
+    pub base_value: Option<String>,
+    /// Value now in user.ltx.
+    pub disk_value: Option<String>,
+    /// Value the user wants to save.
+    pub pending_value: String,
+}
+
+/// Three-way comparison of pending changes against the on-disk file.
+/// A change conflicts when the disk value moved away from what the UI loaded
+/// and doesn't already equal the pending value. Everything else merges
+/// cleanly by applying the changes on top of the disk version.
+pub fn find_conflicts(
+    base: &OptionsBase,
+    disk: &UserLtx,
+    changes: &[(String, String)],
+) -> Vec<SaveConflict> {
+    let same = |a: Option<&str>, b: Option<&str>| match (a, b) {
+        (Some(a), Some(b)) => console_schema::values_equal(a, b),
+        (None, None) => true,
+        _ => false,
+    };
+
+    changes
+        .iter()
+        .filter_map(|(command, pending)| {
+            let base_value = base.values.get(command).map(|s| s.as_str());
+            let disk_value = disk.get(command);
+            if same(base_value, disk_value) || same(disk_value, Some(pending)) {
+                return None;
+            }
+            Some(SaveConflict {
+                command: command.clone(),
+                display_name: console_schema::display_name(command).map(|s| s.to_string()),
+                base_value: base_value.map(|s| s.to_string()),
+                disk_value: disk_value.map(|s| s.to_string()),
+                pending_value: pending.clone(),
+            })
+        })
+        .collect()
+}
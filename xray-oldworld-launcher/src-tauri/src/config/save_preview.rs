@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::config::console_schema;
+use crate::config::user_ltx::UserLtx;
+
+/// What a `save_options` call would do, computed without touching disk.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavePreview {
+    /// Commands whose value would change, in the order they were submitted.
+    pub changes: Vec<PreviewChange>,
+    /// Commands that would be appended because user.ltx doesn't have them.
+    pub added_keys: Vec<String>,
+    /// Lines of the current file that would no longer be present.
+    pub removed_lines: Vec<String>,
+    pub clears_shader_cache: bool,
+    pub requires_restart: bool,
+    /// The resulting user.ltx contents.
+    pub file_text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewChange {
+    pub command: String,
+    pub display_name: Option<String>,
+    pub before: Option<String>,
+    pub after: String,
+    pub clears_shader_cache: bool,
+    pub requires_restart: bool,
+}
+
+/// Compare the file before and after applying `changes`.
+pub fn build(before: &UserLtx, after: &UserLtx, changes: &[(String, String)]) -> SavePreview {
+    let mut preview_changes: Vec<PreviewChange> = Vec::new();
+    let mut added_keys = Vec::new();
+
+    for (command, value) in changes {
+        let old = before.get(command);
+        if old.is_some_and(|old| old == value) {
+            continue;
+        }
+        if old.is_none() && !added_keys.contains(command) {
+            added_keys.push(command.clone());
+        }
+        // A later change to the same command supersedes an earlier one
+        preview_changes.retain(|c| &c.command != command);
+        preview_changes.push(PreviewChange {
+            command: command.clone(),
+            display_name: console_schema::display_name(command).map(|s| s.to_string()),
+            before: old.map(|s| s.to_string()),
+            after: value.clone(),
+            clears_shader_cache: console_schema::invalidates_shader_cache(command),
+            requires_restart: console_schema::requires_restart(command),
+        });
+    }
+
+    let before_text = before.render();
+    let file_text = after.render();
+
+    SavePreview {
+        clears_shader_cache: preview_changes.iter().any(|c| c.clears_shader_cache),
+        requires_restart: preview_changes.iter().any(|c| c.requires_restart),
+        removed_lines: removed_lines(&before_text, &file_text),
+        changes: preview_changes,
+        added_keys,
+        file_text,
+    }
+}
+
+/// Lines of `before` that don't survive into `after`, counting repeats.
+fn removed_lines(before: &str, after: &str) -> Vec<String> {
+    let mut remaining: HashMap<&str, usize> = HashMap::new();
+    for line in after.lines() {
+        *remaining.entry(line).or_default() += 1;
+    }
+
+    before
+        .lines()
+        .filter(|line| match remaining.get_mut(line) {
+            Some(n) if *n > 0 => {
+                *n -= 1;
+                false
+            }
+            _ => true,
+        })
+        .map(|line| line.to_string())
+        .collect()
+}
@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::axr_options;
+use crate::config::console_schema;
+use crate::config::defaults_diff::keyed_entries;
+use crate::config::ltx_parser::LtxFile;
+use crate::config::user_ltx::UserLtx;
+
+/// Files found in another Anomaly/OWA install.
+#[derive(Debug, Clone)]
+pub struct ImportSource {
+    pub user_ltx: PathBuf,
+    pub axr_options: Option<PathBuf>,
+}
+
+/// What importing would change, shown to the user before anything is written.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    /// Where the settings came from: a user.ltx path, a settings file or a code.
+    pub source_user_ltx: String,
+    pub source_axr_options: Option<String>,
+    /// user.ltx entries that would change, in source file order.
+    pub changes: Vec<ImportChange>,
+    /// axr_options.ltx values that would change.
+    pub axr_changes: Vec<ImportAxrChange>,
+    pub skipped: Vec<SkippedCommand>,
+    /// Source entries that already match this install.
+    pub unchanged: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportChange {
+    pub key: String,
+    pub command: String,
+    pub display_name: Option<String>,
+    pub current_value: Option<String>,
+    pub imported_value: String,
+    /// Original command name when mapped through the migration table.
+    pub renamed_from: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportAxrChange {
+    pub path: String,
+    pub current_value: Option<String>,
+    pub imported_value: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedCommand {
+    pub command: String,
+    pub value: String,
+    pub reason: String,
+}
+
+/// Find user.ltx (and axr_options.ltx, if present) from a path the user picked:
+/// the install root, its appdata folder, or user.ltx itself.
+pub fn locate(path: &Path) -> Result<ImportSource, String> {
+    let user_ltx = if path.is_file() {
+        path.to_path_buf()
+    } else if path.join("appdata").join("user.ltx").is_file() {
+        path.join("appdata").join("user.ltx")
+    } else if path.join("user.ltx").is_file() {
+        path.join("user.ltx")
+    } else {
+        return Err(format!("No user.ltx found in {}", path.display()));
+    };
+
+    // user.ltx lives in <root>/appdata; axr_options.ltx under <root>/gamedata
+    let axr_options = user_ltx
+        .parent()
+        .and_then(|appdata| appdata.parent())
+        .map(axr_options::file_path)
+        .filter(|p| p.is_file());
+
+    Ok(ImportSource {
+        user_ltx,
+        axr_options,
+    })
+}
+
+/// Work out which settings from another install would be imported onto `current`.
+pub fn plan(
+    source: &ImportSource,
+    current: &UserLtx,
+    defaults: &UserLtx,
+    current_axr: &HashMap<String, String>,
+) -> Result<ImportPreview, String> {
+    let content = std::fs::read(&source.user_ltx)
+        .map_err(|e| format!("Failed to read {}: {}", source.user_ltx.display(), e))?;
+    let imported = UserLtx::parse(&String::from_utf8_lossy(&content));
+    let entries: Vec<(String, String)> = keyed_entries(&imported).into_values().collect();
+
+    let axr = match &source.axr_options {
+        Some(path) => {
+            let ltx = LtxFile::parse(path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            axr_options::get_all(&ltx).into_iter().collect()
+        }
+        None => Vec::new(),
+    };
+
+    let mut preview = plan_values(&entries, &axr, current, defaults, current_axr);
+    preview.source_user_ltx = source.user_ltx.display().to_string();
+    preview.source_axr_options = source.axr_options.as_ref().map(|p| p.display().to_string());
+    Ok(preview)
+}
+
+/// Work out which of the given command lines and axr_options values would be
+/// imported onto `current`.
+///
+/// Commands are mapped through the schema's migration table. A command is
+/// imported if the schema or this install's user_default.ltx knows it and,
+/// for schema commands, its value validates; everything else is skipped with
+/// a reason. axr_options values are only imported for options this install
+/// already has (others may belong to another mod or to vanilla Anomaly) and
+/// when `axr_options::check_value` accepts them.
+pub fn plan_values(
+    entries: &[(String, String)],
+    axr: &[(String, String)],
+    current: &UserLtx,
+    defaults: &UserLtx,
+    current_axr: &HashMap<String, String>,
+) -> ImportPreview {
+    let current_entries = keyed_entries(current);
+    let known: Vec<&str> = defaults.commands().map(|(name, _)| name).collect();
+
+    let mut preview = ImportPreview {
+        source_user_ltx: String::new(),
+        source_axr_options: None,
+        changes: Vec::new(),
+        axr_changes: Vec::new(),
+        skipped: Vec::new(),
+        unchanged: 0,
+    };
+
+    for (old_name, old_value) in entries {
+        let skip = |reason: String| SkippedCommand {
+            command: old_name.clone(),
+            value: old_value.clone(),
+            reason,
+        };
+
+        let Some((name, value)) = console_schema::migrate(old_name, old_value) else {
+            preview
+                .skipped
+                .push(skip("no longer supported by this engine".to_string()));
+            continue;
+        };
+
+        // Per-install state, not a setting worth carrying over
+        if name == "_preset" {
+            continue;
+        }
+
+        let key = console_schema::entry_key(name, value);
+        let spec = console_schema::lookup(name);
+        let multi_key = console_schema::split_entry_key(&key).1.is_some();
+        if spec.is_none() && !multi_key && !known.contains(&name) {
+            preview.skipped.push(skip("unknown command".to_string()));
+            continue;
+        }
+        if let Some(spec) = spec
+            && let Err(e) = spec.kind.validate(value)
+        {
+            preview.skipped.push(skip(e));
+            continue;
+        }
+
+        let current_value = current_entries.get(&key).map(|(_, v)| v.clone());
+        if current_value
+            .as_deref()
+            .is_some_and(|v| console_schema::values_equal(v, value))
+        {
+            preview.unchanged += 1;
+            continue;
+        }
+
+        preview.changes.push(ImportChange {
+            key,
+            command: name.to_string(),
+            display_name: console_schema::display_name(name).map(|s| s.to_string()),
+            current_value,
+            imported_value: value.to_string(),
+            renamed_from: (name != old_name).then(|| old_name.clone()),
+        });
+    }
+
+    for (option, value) in axr {
+        let skip = |reason: String| SkippedCommand {
+            command: option.clone(),
+            value: value.clone(),
+            reason,
+        };
+
+        let current_value = match check_axr(option, value, current_axr) {
+            Ok(current_value) => current_value,
+            Err(e) => {
+                preview.skipped.push(skip(e));
+                continue;
+            }
+        };
+        if current_value == value {
+            preview.unchanged += 1;
+            continue;
+        }
+        preview.axr_changes.push(ImportAxrChange {
+            path: option.clone(),
+            current_value: Some(current_value.clone()),
+            imported_value: value.clone(),
+        });
+    }
+    preview.axr_changes.sort_by(|a, b| a.path.cmp(&b.path));
+
+    preview
+}
+
+/// Check an imported axr_options value against this install's options.
+/// Returns the current value, or why the imported one is skipped.
+pub fn check_axr<'a>(
+    option: &str,
+    value: &str,
+    current_axr: &'a HashMap<String, String>,
+) -> Result<&'a String, String> {
+    let current_value = current_axr
+        .get(option)
+        .ok_or_else(|| "not an option of this install".to_string())?;
+    axr_options::check_value(current_value, value)?;
+    Ok(current_value)
+}
+
+/// Apply a previewed import's user.ltx changes; the axr_options changes are
+/// staged with `axr_options::stage_changes`.
+pub fn apply(preview: &ImportPreview, user: &mut UserLtx) {
+    for change in &preview.changes {
+        user.set_entry(&change.key, &change.imported_value);
+    }
+}
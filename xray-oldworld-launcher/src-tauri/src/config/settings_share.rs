@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use serde::{Deserialize, Serialize};
+
+use crate::config::console_schema::{self, SchemaGroup};
+use crate::config::defaults_diff::keyed_entries;
+use crate::config::settings_import::{self, SkippedCommand};
+use crate::config::user_ltx::UserLtx;
+use crate::fs_util;
+
+/// Current share format. Codes are `OWL<version>:<base64url(deflate(json))>`.
+pub const FORMAT_VERSION: u32 = 1;
+const CODE_MAGIC: &str = "OWL";
+/// Cap on the decompressed payload. Real codes are a few KB; anything near
+/// this is a deflate bomb, not settings.
+const MAX_DECODED_BYTES: u64 = 1024 * 1024;
+
+/// A subset of settings someone chose to share.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedSettings {
+    pub version: u32,
+    pub groups: Vec<SchemaGroup>,
+    /// user.ltx lines as (command, value), in file order.
+    pub user_ltx: Vec<(String, String)>,
+    /// axr_options.ltx values as (path, value).
+    pub axr_options: Vec<(String, String)>,
+}
+
+/// Collect the settings in `groups`. Schema commands are always included;
+/// other commands only when they differ from user_default.ltx, which keeps
+/// codes short enough to paste.
+pub fn collect(
+    user: &UserLtx,
+    defaults: &UserLtx,
+    axr: &HashMap<String, String>,
+    groups: &[SchemaGroup],
+) -> SharedSettings {
+    let default_entries = keyed_entries(defaults);
+    let in_groups =
+        |command: &str| console_schema::group_of(command).is_some_and(|g| groups.contains(&g));
+
+    let user_ltx = keyed_entries(user)
+        .into_iter()
+        .filter(|(_, (command, _))| in_groups(command))
+        .filter(|(key, (command, value))| {
+            console_schema::lookup(command).is_some()
+                || !default_entries
+                    .get(key)
+                    .is_some_and(|(_, d)| console_schema::values_equal(d, value))
+        })
+        .map(|(_, entry)| entry)
+        .collect();
+
+    let mut axr_options: Vec<(String, String)> = axr
+        .iter()
+        .filter(|(path, _)| {
+            groups
+                .iter()
+                .any(|g| path.split('/').next() == Some(g.page_id()))
+        })
+        .map(|(path, value)| (path.clone(), value.clone()))
+        .collect();
+    axr_options.sort();
+
+    SharedSettings {
+        version: FORMAT_VERSION,
+        groups: groups.to_vec(),
+        user_ltx,
+        axr_options,
+    }
+}
+
+/// Drop entries outside the groups the share claims to contain, so a code
+/// labelled "graphics" can't quietly change binds or gameplay commands, and
+/// axr_options values that aren't options of this install or don't fit them
+/// (see `settings_import::check_axr`). Returns what was dropped and why.
+pub fn restrict_to_groups(
+    shared: &mut SharedSettings,
+    current_axr: &HashMap<String, String>,
+) -> Vec<SkippedCommand> {
+    let groups = shared.groups.clone();
+    let in_groups = |g: Option<SchemaGroup>| g.is_some_and(|g| groups.contains(&g));
+    let skip = |(command, value): (String, String), reason: &str| SkippedCommand {
+        command,
+        value,
+        reason: reason.to_string(),
+    };
+    let outside = "outside the shared setting groups";
+
+    let (kept, dropped): (Vec<_>, Vec<_>) = shared
+        .user_ltx
+        .drain(..)
+        .partition(|(command, _)| in_groups(console_schema::group_of(command)));
+    shared.user_ltx = kept;
+    let mut skipped: Vec<SkippedCommand> = dropped
+        .into_iter()
+        .map(|entry| skip(entry, outside))
+        .collect();
+
+    let mut kept = Vec::new();
+    for (path, value) in shared.axr_options.drain(..) {
+        let page = path.split('/').next();
+        if !groups.iter().any(|g| page == Some(g.page_id())) {
+            skipped.push(skip((path, value), outside));
+        } else if let Err(e) = settings_import::check_axr(&path, &value, current_axr) {
+            skipped.push(skip((path, value), &e));
+        } else {
+            kept.push((path, value));
+        }
+    }
+    shared.axr_options = kept;
+
+    skipped
+}
+
+/// Whether `text` looks like a settings code rather than a file path.
+pub fn is_code(text: &str) -> bool {
+    let text = text.trim();
+    text.strip_prefix(CODE_MAGIC)
+        .and_then(|rest| rest.split_once(':'))
+        .is_some_and(|(version, _)| {
+            !version.is_empty() && version.chars().all(|c| c.is_ascii_digit())
+        })
+}
+
+/// Encode as a compact copy-pasteable string.
+pub fn encode(shared: &SharedSettings) -> Result<String, String> {
+    let json =
+        serde_json::to_vec(shared).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(&json)
+        .and_then(|_| encoder.finish())
+        .map(|compressed| {
+            format!(
+                "{}{}:{}",
+                CODE_MAGIC,
+                shared.version,
+                URL_SAFE_NO_PAD.encode(compressed)
+            )
+        })
+        .map_err(|e| format!("Failed to compress settings: {}", e))
+}
+
+/// Decode a settings code produced by `encode`.
+pub fn decode(code: &str) -> Result<SharedSettings, String> {
+    let code: String = code.split_whitespace().collect();
+    let (version, payload) = code
+        .strip_prefix(CODE_MAGIC)
+        .and_then(|rest| rest.split_once(':'))
+        .ok_or_else(|| "Not a settings code".to_string())?;
+    let version: u32 = version
+        .parse()
+        .map_err(|_| "Not a settings code".to_string())?;
+    check_version(version)?;
+
+    let compressed = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| "Settings code is damaged (invalid characters)".to_string())?;
+    let mut json = Vec::new();
+    DeflateDecoder::new(compressed.as_slice())
+        .take(MAX_DECODED_BYTES)
+        .read_to_end(&mut json)
+        .map_err(|_| "Settings code is damaged or incomplete".to_string())?;
+    if json.len() as u64 >= MAX_DECODED_BYTES {
+        return Err("Settings code is invalid: too large".to_string());
+    }
+
+    let shared: SharedSettings =
+        serde_json::from_slice(&json).map_err(|e| format!("Settings code is invalid: {}", e))?;
+    if shared.version != version {
+        return Err("Settings code is invalid: version mismatch".to_string());
+    }
+    Ok(shared)
+}
+
+/// Write a shared settings file (pretty JSON, readable and diffable).
+pub fn write_file(path: &Path, shared: &SharedSettings) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(shared)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs_util::write_atomic(path, json, false)
+}
+
+/// Read a shared settings file written by `write_file`.
+pub fn read_file(path: &Path) -> Result<SharedSettings, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let shared: SharedSettings = serde_json::from_str(&content)
+        .map_err(|e| format!("{} is not a settings file: {}", path.display(), e))?;
+    check_version(shared.version)?;
+    Ok(shared)
+}
+
+fn check_version(version: u32) -> Result<(), String> {
+    if version == 0 {
+        return Err("Settings are invalid: unknown format 0".to_string());
+    }
+    if version > FORMAT_VERSION {
+        return Err(format!(
+            "These settings were made by a newer launcher (format {}); update to import them",
+            version
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_axr_keys_in_codes_are_skipped() {
+        let shared = SharedSettings {
+            version: FORMAT_VERSION,
+            groups: vec![SchemaGroup::Video],
+            user_ltx: Vec::new(),
+            axr_options: vec![
+                ("video/basic/brightness".to_string(), "0.8".to_string()),
+                ("video/basic/gamma".to_string(), "bright".to_string()),
+                ("video/hidden/unlock_all".to_string(), "true".to_string()),
+            ],
+        };
+        let mut decoded = decode(&encode(&shared).unwrap()).unwrap();
+        let current_axr = HashMap::from([
+            ("video/basic/brightness".to_string(), "1.0".to_string()),
+            ("video/basic/gamma".to_string(), "1.0".to_string()),
+        ]);
+
+        let skipped = restrict_to_groups(&mut decoded, &current_axr);
+        assert_eq!(
+            decoded.axr_options,
+            vec![("video/basic/brightness".to_string(), "0.8".to_string())]
+        );
+        let skipped: Vec<&str> = skipped.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(
+            skipped,
+            vec!["video/basic/gamma", "video/hidden/unlock_all"]
+        );
+    }
+}
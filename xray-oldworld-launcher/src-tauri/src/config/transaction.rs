@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::fs_util;
+use crate::logging;
+
+/// A set of config file writes applied all-or-nothing.
+///
+/// Writes are staged in memory, checked up front, then committed one file at
+/// a time with `write_atomic`. If any write fails, every file already written
+/// is restored to its previous contents (or removed if it didn't exist), so
+/// the game never starts with half-applied settings.
+#[derive(Default)]
+pub struct ConfigTransaction {
+    staged: Vec<StagedWrite>,
+}
+
+struct StagedWrite {
+    path: PathBuf,
+    contents: Vec<u8>,
+    backup: bool,
+}
+
+impl ConfigTransaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stage a full replacement of `path`. Staging the same path twice keeps the last contents.
+    pub fn stage(&mut self, path: &Path, contents: impl Into<Vec<u8>>, backup: bool) {
+        self.staged.retain(|w| w.path != path);
+        self.staged.push(StagedWrite {
+            path: path.to_path_buf(),
+            contents: contents.into(),
+            backup,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.staged.is_empty()
+    }
+
+    /// Check every staged write can be attempted before touching anything.
+    fn validate(&self) -> Result<(), String> {
+        let mut problems = Vec::new();
+        for write in &self.staged {
+            match write.path.parent() {
+                Some(dir) if dir.is_dir() => {}
+                _ => problems.push(format!("{:?}: directory does not exist", write.path)),
+            }
+            if let Ok(meta) = fs::metadata(&write.path) {
+                if meta.is_dir() {
+                    problems.push(format!("{:?} is a directory", write.path));
+                } else if meta.permissions().readonly() {
+                    problems.push(format!("{:?} is read-only", write.path));
+                }
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Cannot apply settings: {}", problems.join("; ")))
+        }
+    }
+
+    /// Validate and write all staged files, rolling back on the first failure.
+    pub fn commit(self) -> Result<(), String> {
+        self.validate()?;
+
+        let mut originals: Vec<(&Path, Option<Vec<u8>>)> = Vec::new();
+        for write in &self.staged {
+            let original = if write.path.exists() {
+                Some(
+                    fs::read(&write.path)
+                        .map_err(|e| format!("Failed to read {:?}: {}", write.path, e))?,
+                )
+            } else {
+                None
+            };
+
+            if let Err(e) = fs_util::write_atomic(&write.path, &write.contents, write.backup) {
+                logging::log(format!("ERROR: Transaction write failed: {}", e));
+                let rollback_errors = rollback(&originals);
+                if rollback_errors.is_empty() {
+                    return Err(format!("{} (all changes rolled back)", e));
+                }
+                return Err(format!(
+                    "{} — rollback also failed: {}",
+                    e,
+                    rollback_errors.join("; ")
+                ));
+            }
+            originals.push((&write.path, original));
+        }
+
+        logging::log(format!(
+            "Transaction committed: {}",
+            self.staged
+                .iter()
+                .map(|w| w.path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+        Ok(())
+    }
+}
+
+/// Restore files in reverse order. Returns the errors encountered.
+fn rollback(originals: &[(&Path, Option<Vec<u8>>)]) -> Vec<String> {
+    let mut errors = Vec::new();
+    for (path, original) in originals.iter().rev() {
+        let result = match original {
+            Some(contents) => fs_util::write_atomic(path, contents, false),
+            None => fs::remove_file(path).map_err(|e| format!("Failed to remove {:?}: {}", path, e)),
+        };
+        match result {
+            Ok(()) => logging::log(format!("Rolled back {}", path.display())),
+            Err(e) => errors.push(e),
+        }
+    }
+    errors
+}
@@ -2,30 +2,99 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+use crate::config::console_schema;
+use crate::fs_util;
 use crate::logging;
 
 /// Represents a parsed user.ltx file.
 /// user.ltx uses a flat format with no sections: `command value` per line.
+#[derive(Debug, Clone)]
 pub struct UserLtx {
     /// Ordered entries preserving file structure for write-back.
     entries: Vec<UserLtxEntry>,
     /// Fast lookup: command name -> index in entries vec.
     index: HashMap<String, usize>,
+    /// Line ending, BOM and final newline of the file as read, reproduced on save.
+    format: FileFormat,
 }
 
 #[derive(Debug, Clone)]
 enum UserLtxEntry {
-    Command { name: String, value: String },
+    /// `raw` is the original line, kept until the value changes so untouched
+    /// lines are written back byte-for-byte.
+    Command {
+        name: String,
+        value: String,
+        raw: Option<String>,
+    },
     Comment(String),
     Empty,
 }
 
+impl UserLtxEntry {
+    fn command(name: &str, value: &str) -> Self {
+        UserLtxEntry::Command {
+            name: name.to_string(),
+            value: value.to_string(),
+            raw: None,
+        }
+    }
+}
+
+/// Read-only view of one line of user.ltx, in file order.
+#[derive(Debug, Clone, Copy)]
+pub enum LineView<'a> {
+    Command { name: &'a str, value: &'a str },
+    Comment(&'a str),
+    Empty,
+}
+
+/// Textual style of a user.ltx file. The game on Windows writes CRLF, and
+/// some editors add a UTF-8 BOM; both must survive a launcher save.
+#[derive(Debug, Clone, Copy)]
+struct FileFormat {
+    crlf: bool,
+    bom: bool,
+    trailing_newline: bool,
+}
+
+impl Default for FileFormat {
+    fn default() -> Self {
+        // New files follow the game's own convention for the platform
+        FileFormat {
+            crlf: cfg!(target_os = "windows"),
+            bom: false,
+            trailing_newline: true,
+        }
+    }
+}
+
+impl FileFormat {
+    fn detect(content: &str) -> Self {
+        let bom = content.starts_with('\u{FEFF}');
+        let crlf = match content.find('\n') {
+            Some(pos) => content[..pos].ends_with('\r'),
+            None => false,
+        };
+        FileFormat {
+            crlf,
+            bom,
+            trailing_newline: content.is_empty() || content.ends_with('\n'),
+        }
+    }
+
+    fn newline(&self) -> &'static str {
+        if self.crlf { "\r\n" } else { "\n" }
+    }
+}
+
 #[allow(dead_code)]
 impl UserLtx {
     pub fn new() -> Self {
         UserLtx {
             entries: Vec::new(),
             index: HashMap::new(),
+            format: FileFormat::default(),
         }
     }
 
@@ -40,12 +109,18 @@ impl UserLtx {
             return Self::new();
         }
 
-        match fs::read_to_string(&path) {
-            Ok(content) => {
+        // Read lossily: a file damaged by a crash may not be valid UTF-8, and
+        // treating it as empty would let the next save wipe every other command.
+        match fs::read(&path) {
+            Ok(bytes) => {
+                let content = String::from_utf8_lossy(&bytes);
+                if matches!(content, std::borrow::Cow::Owned(_)) {
+                    logging::log("WARNING: user.ltx is not valid UTF-8, reading it lossily");
+                }
                 let ltx = Self::parse(&content);
                 logging::log(format!(
                     "user.ltx loaded: {} bytes, {} commands parsed",
-                    content.len(),
+                    bytes.len(),
                     ltx.index.len()
                 ));
                 ltx
@@ -65,14 +140,21 @@ impl UserLtx {
             return Self::load(appdata_path);
         }
 
-        let default_path = game_root.join("user_default.ltx");
+        logging::log("user.ltx not found, falling back to user_default.ltx");
+        Self::load_defaults(game_root)
+    }
+
+    /// Load the OWA defaults (user_default.ltx) from the game root.
+    /// Returns empty state if the file doesn't exist.
+    pub fn load_defaults(game_root: &Path) -> Self {
+        let default_path = Self::defaults_path(game_root);
         logging::log(format!(
-            "user.ltx not found, falling back to user_default.ltx at: {}",
+            "Loading user_default.ltx from: {}",
             default_path.display()
         ));
 
         if !default_path.exists() {
-            logging::log("user_default.ltx also not found, returning empty state");
+            logging::log("user_default.ltx not found, returning empty state");
             return Self::new();
         }
 
@@ -92,9 +174,12 @@ impl UserLtx {
         }
     }
 
-    /// Parse user.ltx content.
-    fn parse(content: &str) -> Self {
+    /// Parse user.ltx content. A leading UTF-8 BOM is stripped so it doesn't
+    /// become part of the first command name.
+    pub fn parse(content: &str) -> Self {
         let mut ltx = UserLtx::new();
+        ltx.format = FileFormat::detect(content);
+        let content = content.strip_prefix('\u{FEFF}').unwrap_or(content);
 
         for line in content.lines() {
             let trimmed = line.trim();
@@ -123,7 +208,11 @@ impl UserLtx {
 
             let idx = ltx.entries.len();
             ltx.index.insert(name.clone(), idx);
-            ltx.entries.push(UserLtxEntry::Command { name, value });
+            ltx.entries.push(UserLtxEntry::Command {
+                name,
+                value,
+                raw: Some(line.to_string()),
+            });
         }
 
         ltx
@@ -144,7 +233,7 @@ impl UserLtx {
     pub fn get_all(&self) -> HashMap<String, String> {
         let mut result = HashMap::new();
         for entry in &self.entries {
-            if let UserLtxEntry::Command { name, value } = entry {
+            if let UserLtxEntry::Command { name, value, .. } = entry {
                 result.insert(name.clone(), value.clone());
             }
         }
@@ -153,67 +242,164 @@ impl UserLtx {
 
     /// Set a console command value. Adds if not present, updates if exists.
     pub fn set(&mut self, command: &str, value: &str) {
+        if self.get(command) == Some(value) {
+            // Unchanged: keep the original line as-is
+            return;
+        }
         if let Some(&idx) = self.index.get(command) {
-            self.entries[idx] = UserLtxEntry::Command {
-                name: command.to_string(),
-                value: value.to_string(),
-            };
+            self.entries[idx] = UserLtxEntry::command(command, value);
         } else {
             let idx = self.entries.len();
             self.index.insert(command.to_string(), idx);
-            self.entries.push(UserLtxEntry::Command {
-                name: command.to_string(),
-                value: value.to_string(),
-            });
+            self.entries.push(UserLtxEntry::command(command, value));
+        }
+    }
+
+    /// Iterate over all command lines in file order as (name, value).
+    /// Unlike `get_all`, repeated commands such as `bind` are all returned.
+    pub fn commands(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().filter_map(|entry| match entry {
+            UserLtxEntry::Command { name, value, .. } => Some((name.as_str(), value.as_str())),
+            _ => None,
+        })
+    }
+
+    /// Iterate over every line, including comments and blanks. The position
+    /// in the iteration is the 0-based line number in the file as loaded.
+    pub fn lines(&self) -> impl Iterator<Item = LineView<'_>> {
+        self.entries.iter().map(|entry| match entry {
+            UserLtxEntry::Command { name, value, .. } => LineView::Command { name, value },
+            UserLtxEntry::Comment(text) => LineView::Comment(text),
+            UserLtxEntry::Empty => LineView::Empty,
+        })
+    }
+
+    /// Set the line identified by an entry key (see `console_schema::entry_key`).
+    /// For plain commands this is the same as `set`; for bind-style keys it
+    /// replaces only the line for that action. `value` is the full line value.
+    pub fn set_entry(&mut self, key: &str, value: &str) {
+        let (name, action) = console_schema::split_entry_key(key);
+        if action.is_none() {
+            self.set(name, value);
+            return;
+        }
+
+        if let Some(idx) = self.find_entry(key) {
+            if matches!(&self.entries[idx], UserLtxEntry::Command { value: v, .. } if v == value) {
+                return;
+            }
+            self.entries[idx] = UserLtxEntry::command(name, value);
+            return;
+        }
+
+        let idx = self.entries.len();
+        self.index.insert(name.to_string(), idx);
+        self.entries.push(UserLtxEntry::command(name, value));
+    }
+
+    /// Remove the line identified by an entry key.
+    pub fn remove_entry(&mut self, key: &str) -> bool {
+        let (name, action) = console_schema::split_entry_key(key);
+        if action.is_none() {
+            return self.remove(name);
+        }
+
+        match self.find_entry(key) {
+            Some(idx) => {
+                self.entries.remove(idx);
+                self.rebuild_index();
+                true
+            }
+            None => false,
         }
     }
 
+    fn find_entry(&self, key: &str) -> Option<usize> {
+        self.entries.iter().position(|entry| {
+            matches!(entry, UserLtxEntry::Command { name, value, .. }
+                if console_schema::entry_key(name, value) == key)
+        })
+    }
+
     /// Check if a command exists.
     pub fn has(&self, command: &str) -> bool {
         self.index.contains_key(command)
     }
 
-    /// Remove a command entry.
+    /// Remove a command, dropping every line for it from the file.
     pub fn remove(&mut self, command: &str) -> bool {
-        if let Some(&idx) = self.index.get(command) {
-            self.entries[idx] = UserLtxEntry::Empty;
-            self.index.remove(command);
-            true
-        } else {
-            false
+        if !self.index.contains_key(command) {
+            return false;
+        }
+        self.entries
+            .retain(|e| !matches!(e, UserLtxEntry::Command { name, .. } if name == command));
+        self.rebuild_index();
+        true
+    }
+
+    /// Point each command at its last line, as `parse` does.
+    fn rebuild_index(&mut self) {
+        self.index.clear();
+        for (idx, entry) in self.entries.iter().enumerate() {
+            if let UserLtxEntry::Command { name, .. } = entry {
+                self.index.insert(name.clone(), idx);
+            }
         }
     }
 
     /// Save user.ltx to disk, preserving structure.
     pub fn save(&self, appdata_path: &Path) -> Result<(), String> {
+        Self::write_file(appdata_path, self.render())
+            .map_err(|e| format!("Failed to write user.ltx: {}", e))
+    }
+
+    /// Replace user.ltx with `content`. A read-only user.ltx is the player's
+    /// lock against the game resetting it; every launcher write is an
+    /// explicit choice, so it unlocks the file and locks it again.
+    pub fn write_file(appdata_path: &Path, content: impl AsRef<[u8]>) -> Result<(), String> {
         let path = Self::file_path(appdata_path);
-        let mut output = String::new();
+        fs_util::with_unlocked(&path, || fs_util::write_atomic(&path, content, true))
+    }
 
-        for entry in &self.entries {
-            match entry {
-                UserLtxEntry::Command { name, value } => {
+    /// Render the file text as `save` would write it, in the original
+    /// line-ending and BOM style.
+    pub fn render(&self) -> String {
+        let lines: Vec<String> = self
+            .entries
+            .iter()
+            .map(|entry| match entry {
+                UserLtxEntry::Command {
+                    raw: Some(raw), ..
+                } => raw.clone(),
+                UserLtxEntry::Command { name, value, .. } => {
                     if value.is_empty() {
-                        output.push_str(name);
+                        name.clone()
                     } else {
-                        output.push_str(&format!("{} {}", name, value));
+                        format!("{} {}", name, value)
                     }
-                    output.push('\n');
-                }
-                UserLtxEntry::Comment(text) => {
-                    output.push_str(text);
-                    output.push('\n');
                 }
-                UserLtxEntry::Empty => {
-                    output.push('\n');
-                }
-            }
-        }
+                UserLtxEntry::Comment(text) => text.clone(),
+                UserLtxEntry::Empty => String::new(),
+            })
+            .collect();
 
-        fs::write(&path, output)
-            .map_err(|e| format!("Failed to write user.ltx to {:?}: {}", path, e))
+        let newline = self.format.newline();
+        let mut output = String::new();
+        if self.format.bom {
+            output.push('\u{FEFF}');
+        }
+        output.push_str(&lines.join(newline));
+        if self.format.trailing_newline && !lines.is_empty() {
+            output.push_str(newline);
+        }
+        output
     }
 
-    fn file_path(appdata_path: &Path) -> std::path::PathBuf {
+    pub fn file_path(appdata_path: &Path) -> std::path::PathBuf {
         appdata_path.join("user.ltx")
     }
+
+    pub fn defaults_path(game_root: &Path) -> std::path::PathBuf {
+        game_root.join("user_default.ltx")
+    }
 }
@@ -0,0 +1,149 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::logging;
+
+/// Write a file so that readers only ever see the old or the new contents.
+///
+/// The data goes to a temp file in the same directory, is fsynced, and then
+/// renamed over the target. The original file's permissions are carried over.
+/// With `backup`, the previous contents are kept as `<name>.bak`.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>, backup: bool) -> Result<(), String> {
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("Invalid file path: {:?}", path))?
+        .to_string_lossy()
+        .to_string();
+
+    let existing = fs::metadata(path).ok();
+    if let Some(meta) = &existing
+        && meta.permissions().readonly()
+    {
+        return Err(format!(
+            "{:?} is read-only. Clear the read-only attribute to let the launcher save it.",
+            path
+        ));
+    }
+
+    let tmp_path = dir.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+    let result = write_and_replace(path, &tmp_path, contents.as_ref(), existing, backup);
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+fn write_and_replace(
+    path: &Path,
+    tmp_path: &Path,
+    contents: &[u8],
+    existing: Option<fs::Metadata>,
+    backup: bool,
+) -> Result<(), String> {
+    let mut tmp = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(tmp_path)
+        .map_err(|e| format!("Failed to create temp file {:?}: {}", tmp_path, e))?;
+    tmp.write_all(contents)
+        .map_err(|e| format!("Failed to write {:?}: {}", tmp_path, e))?;
+    tmp.sync_all()
+        .map_err(|e| format!("Failed to flush {:?}: {}", tmp_path, e))?;
+    drop(tmp);
+
+    if let Some(meta) = existing {
+        fs::set_permissions(tmp_path, meta.permissions())
+            .map_err(|e| format!("Failed to copy permissions to {:?}: {}", tmp_path, e))?;
+
+        if backup {
+            let bak = backup_path(path);
+            fs::copy(path, &bak)
+                .map_err(|e| format!("Failed to back up {:?} to {:?}: {}", path, bak, e))?;
+        }
+    }
+
+    fs::rename(tmp_path, path)
+        .map_err(|e| format!("Failed to replace {:?}: {}", path, e))?;
+
+    // Persist the rename itself. Not supported for directories on Windows.
+    #[cfg(unix)]
+    if let Some(dir) = path.parent()
+        && let Ok(d) = fs::File::open(dir)
+    {
+        let _ = d.sync_all();
+    }
+
+    Ok(())
+}
+
+/// Path of the `.bak` copy kept by `write_atomic`.
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Whether `path` exists and is marked read-only.
+pub fn is_read_only(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.permissions().readonly())
+}
+
+/// Mark a file read-only or writable. On Unix this clears every write bit
+/// or restores the owner's; on Windows it toggles the read-only attribute.
+pub fn set_read_only(path: &Path, read_only: bool) -> Result<(), String> {
+    let mut perms = fs::metadata(path)
+        .map_err(|e| format!("Failed to read {:?}: {}", path, e))?
+        .permissions();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = perms.mode();
+        perms.set_mode(if read_only { mode & !0o222 } else { mode | 0o200 });
+    }
+    #[cfg(not(unix))]
+    perms.set_readonly(read_only);
+
+    fs::set_permissions(path, perms).map_err(|e| {
+        format!(
+            "Failed to make {:?} {}: {}",
+            path,
+            if read_only { "read-only" } else { "writable" },
+            e
+        )
+    })
+}
+
+/// Run `f` with `path` temporarily writable if it was read-only, and make it
+/// read-only again afterwards whether or not `f` succeeded.
+pub fn with_unlocked<T>(path: &Path, f: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    if !is_read_only(path) {
+        return f();
+    }
+
+    set_read_only(path, false)?;
+    let result = f();
+    if let Err(e) = set_read_only(path, true) {
+        logging::log(format!("WARNING: {}", e));
+    }
+    result
+}
+
+/// Probe by creating a file: permission bits alone don't reveal read-only
+/// mounts (squashfs) or Windows folder ACLs.
+pub fn is_writable_dir(dir: &Path) -> bool {
+    let probe = dir.join(format!(".owl_write_test.{}", std::process::id()));
+    match fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
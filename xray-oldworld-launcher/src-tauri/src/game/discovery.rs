@@ -0,0 +1,477 @@
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::logging;
+
+/// Upper bound on directories visited per discovery run, so a huge mounted
+/// drive can't stall the UI.
+const MAX_VISITED_DIRS: usize = 40_000;
+
+/// Directory names never worth descending into.
+const SKIP_DIRS: &[&str] = &[
+    "windows",
+    "appdata",
+    "node_modules",
+    "$recycle.bin",
+    "system volume information",
+    "proc",
+    "sys",
+    "dev",
+];
+
+/// Where a candidate was found, most specific first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiscoverySource {
+    /// Next to the launcher executable.
+    Launcher,
+    Steam,
+    /// A Steam Proton prefix (`compatdata/*/pfx/drive_c`).
+    Proton,
+    Lutris,
+    Heroic,
+    /// A plain Wine prefix.
+    Wine,
+    Home,
+    Drive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InstallKind {
+    /// Has the Old World executables.
+    OldWorld,
+    /// Plain Anomaly (or another addon) without them; still useful as a
+    /// settings import source.
+    Anomaly,
+}
+
+/// A folder that looks like a game install.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallCandidate {
+    pub game_root: String,
+    pub source: DiscoverySource,
+    pub kind: InstallKind,
+    /// From a version.txt the mod ships, else the exe's file version.
+    pub version: Option<String>,
+    /// `bin/OldWorldDX11*.exe` (or `AnomalyDX11*.exe`) names found.
+    pub executables: Vec<String>,
+    pub has_fsgame: bool,
+    /// appdata/user.ltx exists, i.e. the game has been run.
+    pub has_user_ltx: bool,
+    /// Higher is a better match; candidates are sorted by it.
+    pub score: u32,
+}
+
+/// Scan likely install locations and return candidates, best first.
+pub fn discover() -> Vec<InstallCandidate> {
+    let mut scan = Scan::default();
+    for (root, source, depth) in search_roots() {
+        scan.walk(&root, source, depth);
+    }
+    logging::log(format!(
+        "Install discovery: {} candidate(s), {} dirs visited",
+        scan.found.len(),
+        scan.visited
+    ));
+
+    let mut found = scan.found;
+    found.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(a.source.cmp(&b.source))
+            .then(a.game_root.cmp(&b.game_root))
+    });
+    found
+}
+
+/// Inspect `dir` as a possible game root. Needs `gamedata/` plus an exe or
+/// fsgame.ltx.
+pub fn inspect(dir: &Path, source: DiscoverySource) -> Option<InstallCandidate> {
+    if !dir.join("gamedata").is_dir() {
+        return None;
+    }
+    let old_world = executables(dir, "oldworlddx11");
+    let (kind, executables) = if old_world.is_empty() {
+        (InstallKind::Anomaly, executables(dir, "anomalydx11"))
+    } else {
+        (InstallKind::OldWorld, old_world)
+    };
+    let has_fsgame = dir.join("fsgame.ltx").is_file();
+    if executables.is_empty() && !has_fsgame {
+        return None;
+    }
+
+    let has_user_ltx = dir.join("appdata").join("user.ltx").is_file();
+    let score = match kind {
+        InstallKind::OldWorld => 8,
+        InstallKind::Anomaly => 0,
+    } + if has_fsgame { 4 } else { 0 }
+        + if executables.is_empty() { 0 } else { 2 }
+        + u32::from(has_user_ltx);
+
+    let version = version_file(dir).or_else(|| {
+        executables
+            .first()
+            .and_then(|exe| exe_file_version(&dir.join("bin").join(exe)))
+    });
+
+    Some(InstallCandidate {
+        game_root: dir.display().to_string(),
+        source,
+        kind,
+        version,
+        executables,
+        has_fsgame,
+        has_user_ltx,
+        score,
+    })
+}
+
+#[derive(Default)]
+struct Scan {
+    found: Vec<InstallCandidate>,
+    seen: HashSet<PathBuf>,
+    visited: usize,
+}
+
+impl Scan {
+    /// Check `dir`, then its subdirectories down to `depth` levels. Stops
+    /// at an install; symlinked subdirectories are not followed.
+    fn walk(&mut self, dir: &Path, source: DiscoverySource, depth: usize) {
+        if self.visited >= MAX_VISITED_DIRS {
+            return;
+        }
+        let key = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        if !self.seen.insert(key) {
+            return;
+        }
+        self.visited += 1;
+
+        if let Some(candidate) = inspect(dir, source) {
+            self.found.push(candidate);
+            return;
+        }
+        if depth == 0 {
+            return;
+        }
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            if is_dir && !name.starts_with('.') && !SKIP_DIRS.contains(&name.as_str()) {
+                self.walk(&entry.path(), source, depth - 1);
+            }
+        }
+    }
+}
+
+/// (dir, source, depth) to scan, most specific first so duplicates keep
+/// the better label.
+fn search_roots() -> Vec<(PathBuf, DiscoverySource, usize)> {
+    let mut roots = Vec::new();
+    let home = home_dir();
+
+    if let Some(dir) = env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+    {
+        if let Some(parent) = dir.parent() {
+            roots.push((parent.to_path_buf(), DiscoverySource::Launcher, 0));
+        }
+        roots.push((dir, DiscoverySource::Launcher, 0));
+    }
+
+    for library in steam_libraries(home.as_deref()) {
+        let steamapps = library.join("steamapps");
+        roots.push((steamapps.join("common"), DiscoverySource::Steam, 2));
+        for prefix in subdirs(&steamapps.join("compatdata")) {
+            roots.push((
+                prefix.join("pfx").join("drive_c"),
+                DiscoverySource::Proton,
+                4,
+            ));
+        }
+    }
+
+    if let Some(home) = &home {
+        for dir in lutris_dirs(home) {
+            roots.push((dir.join("drive_c"), DiscoverySource::Lutris, 4));
+            roots.push((dir, DiscoverySource::Lutris, 2));
+        }
+        for dir in heroic_dirs(home) {
+            roots.push((dir.join("drive_c"), DiscoverySource::Heroic, 4));
+            roots.push((dir, DiscoverySource::Heroic, 2));
+        }
+
+        let mut prefixes = vec![home.join(".wine")];
+        prefixes.extend(env::var_os("WINEPREFIX").map(PathBuf::from));
+        for prefix in prefixes {
+            roots.push((prefix.join("drive_c"), DiscoverySource::Wine, 4));
+        }
+
+        roots.push((home.clone(), DiscoverySource::Home, 3));
+    }
+
+    for drive in mounted_drives() {
+        roots.push((drive, DiscoverySource::Drive, 3));
+    }
+
+    roots.retain(|(dir, _, _)| dir.is_dir());
+    roots
+}
+
+fn home_dir() -> Option<PathBuf> {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    env::var_os(var).map(PathBuf::from)
+}
+
+/// Steam library roots from every libraryfolders.vdf we can find.
+fn steam_libraries(home: Option<&Path>) -> Vec<PathBuf> {
+    let mut steam_dirs = Vec::new();
+    if cfg!(windows) {
+        for var in ["ProgramFiles(x86)", "ProgramFiles"] {
+            steam_dirs.extend(env::var_os(var).map(|p| PathBuf::from(p).join("Steam")));
+        }
+    } else if let Some(home) = home {
+        steam_dirs.push(home.join(".steam").join("steam"));
+        steam_dirs.push(home.join(".local").join("share").join("Steam"));
+        // Flatpak Steam
+        steam_dirs.push(home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"));
+    }
+
+    let mut libraries = steam_dirs.clone();
+    for steam in &steam_dirs {
+        let vdf = steam.join("steamapps").join("libraryfolders.vdf");
+        if let Ok(content) = fs::read_to_string(&vdf) {
+            libraries.extend(parse_library_folders(&content));
+        }
+    }
+    libraries
+}
+
+/// `"path"  "D:\\SteamLibrary"` lines of libraryfolders.vdf.
+fn parse_library_folders(content: &str) -> Vec<PathBuf> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut quoted = line.split('"').skip(1).step_by(2);
+            match (quoted.next(), quoted.next()) {
+                (Some(key), Some(value)) if key.eq_ignore_ascii_case("path") => {
+                    Some(PathBuf::from(value.replace("\\\\", "\\")))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Game dirs and prefixes from Lutris game configs, plus its default dir.
+fn lutris_dirs(home: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![home.join("Games")];
+    let configs = [
+        home.join(".config/lutris/games"),
+        home.join(".local/share/lutris/games"),
+    ];
+    for config in configs.iter().flat_map(|dir| files(dir)) {
+        let Ok(content) = fs::read_to_string(&config) else {
+            continue;
+        };
+        for line in content.lines() {
+            let Some((key, value)) = line.trim().split_once(':') else {
+                continue;
+            };
+            let value = PathBuf::from(value.trim().trim_matches(|c| c == '\'' || c == '"'));
+            match key.trim() {
+                "game_path" | "prefix" => dirs.push(value),
+                // bin/OldWorldDX11.exe -> game root
+                "exe" => dirs.extend(value.parent().and_then(Path::parent).map(Path::to_path_buf)),
+                _ => {}
+            }
+        }
+    }
+    dirs
+}
+
+/// Heroic prefixes and sideloaded game install paths.
+fn heroic_dirs(home: &Path) -> Vec<PathBuf> {
+    let heroic = home.join(".config/heroic");
+    let mut dirs = subdirs(&home.join("Games/Heroic/Prefixes"));
+    let configs = files(&heroic.join("GamesConfig"))
+        .into_iter()
+        .chain([heroic.join("sideload_apps/library.json")]);
+    for config in configs {
+        let json = fs::read_to_string(&config)
+            .ok()
+            .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok());
+        if let Some(json) = json {
+            collect_json_paths(&json, &mut dirs);
+        }
+    }
+    dirs
+}
+
+fn collect_json_paths(value: &serde_json::Value, out: &mut Vec<PathBuf>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                match value.as_str() {
+                    Some(path) if matches!(key.as_str(), "winePrefix" | "install_path") => {
+                        out.push(PathBuf::from(path));
+                    }
+                    _ => collect_json_paths(value, out),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_json_paths(item, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn mounted_drives() -> Vec<PathBuf> {
+    if cfg!(windows) {
+        return (b'C'..=b'Z')
+            .map(|letter| PathBuf::from(format!("{}:\\", letter as char)))
+            .filter(|drive| drive.is_dir())
+            .collect();
+    }
+
+    let mut drives = subdirs(Path::new("/mnt"));
+    if let Some(user) = env::var_os("USER") {
+        drives.extend(subdirs(&Path::new("/media").join(&user)));
+        drives.extend(subdirs(&Path::new("/run/media").join(&user)));
+    }
+    drives
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn files(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `bin/<prefix>*.exe` names, case-insensitively (Wine installs vary).
+fn executables(dir: &Path, prefix: &str) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir.join("bin"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .filter(|name| {
+                    let lower = name.to_lowercase();
+                    lower.starts_with(prefix) && lower.ends_with(".exe")
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// First line of a version.txt in the game root or gamedata.
+fn version_file(dir: &Path) -> Option<String> {
+    [
+        dir.join("version.txt"),
+        dir.join("gamedata").join("version.txt"),
+    ]
+    .iter()
+    .find_map(|path| fs::read_to_string(path).ok())
+    .and_then(|content| {
+        content
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+            .map(str::to_string)
+    })
+}
+
+/// Upper bound on how much of the resource section is scanned for the version.
+const MAX_RESOURCE_SCAN_BYTES: u64 = 16 * 1024 * 1024;
+
+/// File version from the exe's VS_FIXEDFILEINFO resource.
+fn exe_file_version(exe: &Path) -> Option<String> {
+    const SIGNATURE: [u8; 4] = 0xFEEF_04BDu32.to_le_bytes();
+    let bytes = read_resource_section(exe)?;
+    let start = bytes.windows(4).position(|w| w == SIGNATURE)?;
+    let word = |offset: usize| {
+        bytes
+            .get(start + offset..start + offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    let (ms, ls) = (word(8)?, word(12)?);
+    if ms == 0 && ls == 0 {
+        return None;
+    }
+    Some(format!(
+        "{}.{}.{}.{}",
+        ms >> 16,
+        ms & 0xFFFF,
+        ls >> 16,
+        ls & 0xFFFF
+    ))
+}
+
+/// Raw bytes of the PE `.rsrc` section, read by seeking through the headers
+/// instead of loading the whole exe.
+fn read_resource_section(exe: &Path) -> Option<Vec<u8>> {
+    let mut file = fs::File::open(exe).ok()?;
+    let mut read_at = |offset: u64, len: usize| -> Option<Vec<u8>> {
+        let mut buf = vec![0; len];
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        file.read_exact(&mut buf).ok()?;
+        Some(buf)
+    };
+    let u16_at = |b: &[u8], i: usize| u16::from_le_bytes([b[i], b[i + 1]]);
+    let u32_at = |b: &[u8], i: usize| u32::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3]]);
+
+    let dos = read_at(0, 64)?;
+    if &dos[..2] != b"MZ" {
+        return None;
+    }
+    let pe = u64::from(u32_at(&dos, 0x3C));
+    let header = read_at(pe, 24)?;
+    if &header[..4] != b"PE\0\0" {
+        return None;
+    }
+    let sections = usize::from(u16_at(&header, 6));
+    let table = pe + 24 + u64::from(u16_at(&header, 20));
+    let table = read_at(table, sections * 40)?;
+    let rsrc = table.chunks_exact(40).find(|s| s.starts_with(b".rsrc\0"))?;
+    let size = u64::from(u32_at(rsrc, 16)).min(MAX_RESOURCE_SCAN_BYTES);
+    let offset = u64::from(u32_at(rsrc, 20));
+    let mut bytes = Vec::new();
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    file.take(size).read_to_end(&mut bytes).ok()?;
+    Some(bytes)
+}
@@ -0,0 +1,286 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::config::integrity::{self, IssueKind};
+use crate::config::launcher_config::LauncherConfig;
+use crate::config::user_ltx::UserLtx;
+use crate::fs_util;
+
+/// Below this much free space the game can fail writing saves or shaders.
+const MIN_FREE_BYTES: u64 = 200 * 1024 * 1024;
+/// Below this, warn: a shader cache rebuild alone can take hundreds of MB.
+const LOW_FREE_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Result of `validate`: every check, passed or not, in a fixed order.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub game_root: String,
+    pub checks: Vec<HealthCheck>,
+    /// No failed check has `Severity::Error`.
+    pub launchable: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckId {
+    GameRoot,
+    StandardExe,
+    AvxExe,
+    Gamedata,
+    DbArchives,
+    Fsgame,
+    AppdataWritable,
+    UserDefaults,
+    DiskSpace,
+}
+
+/// How bad a failed check is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    Info,
+    /// The game starts, but something won't work.
+    Warning,
+    /// The game won't start or can't save.
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheck {
+    pub id: CheckId,
+    pub passed: bool,
+    /// Severity if the check failed.
+    pub severity: Severity,
+    pub detail: String,
+    /// What the user can do about a failure.
+    pub fix: Option<String>,
+}
+
+impl HealthCheck {
+    fn pass(id: CheckId, detail: impl Into<String>) -> Self {
+        HealthCheck {
+            id,
+            passed: true,
+            severity: Severity::Info,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn fail(id: CheckId, severity: Severity, detail: impl Into<String>, fix: &str) -> Self {
+        HealthCheck {
+            id,
+            passed: false,
+            severity,
+            detail: detail.into(),
+            fix: Some(fix.to_string()),
+        }
+    }
+}
+
+/// Check the install at `game_root`. The exe the config will launch is an
+/// error when missing; the other one only a warning.
+pub fn validate(game_root: &Path, appdata: &Path, config: &LauncherConfig) -> HealthReport {
+    let mut checks = Vec::new();
+
+    checks.push(if game_root.is_dir() {
+        HealthCheck::pass(CheckId::GameRoot, game_root.display().to_string())
+    } else {
+        HealthCheck::fail(
+            CheckId::GameRoot,
+            Severity::Error,
+            format!("{} does not exist", game_root.display()),
+            "Choose the game folder in the launcher settings.",
+        )
+    });
+
+    for (id, exe, selected) in [
+        (CheckId::StandardExe, "OldWorldDX11.exe", !config.use_avx),
+        (CheckId::AvxExe, "OldWorldDX11AVX.exe", config.use_avx),
+    ] {
+        let path = game_root.join("bin").join(exe);
+        checks.push(if path.is_file() {
+            HealthCheck::pass(id, format!("bin/{}", exe))
+        } else {
+            let severity = if selected { Severity::Error } else { Severity::Warning };
+            HealthCheck::fail(
+                id,
+                severity,
+                format!("bin/{} is missing", exe),
+                "Reinstall Old World Addon, or check that your antivirus didn't quarantine the executable.",
+            )
+        });
+    }
+
+    checks.push(if game_root.join("gamedata").is_dir() {
+        HealthCheck::pass(CheckId::Gamedata, "gamedata")
+    } else {
+        HealthCheck::fail(
+            CheckId::Gamedata,
+            Severity::Error,
+            "gamedata folder is missing",
+            "Reinstall Old World Addon into this folder.",
+        )
+    });
+
+    let archives = db_archive_count(&game_root.join("db"));
+    checks.push(if archives > 0 {
+        HealthCheck::pass(
+            CheckId::DbArchives,
+            format!("{} archive(s) in db", archives),
+        )
+    } else {
+        HealthCheck::fail(
+            CheckId::DbArchives,
+            Severity::Error,
+            "no .db archives found in the db folder",
+            "Install Anomaly first, then Old World Addon on top of it.",
+        )
+    });
+
+    checks.push(if game_root.join("fsgame.ltx").is_file() {
+        HealthCheck::pass(CheckId::Fsgame, "fsgame.ltx")
+    } else {
+        HealthCheck::fail(
+            CheckId::Fsgame,
+            Severity::Error,
+            "fsgame.ltx is missing",
+            "Restore fsgame.ltx from the Anomaly install.",
+        )
+    });
+
+    checks.push(check_appdata(game_root, appdata));
+    checks.push(check_user_defaults(&game_root.join("user_default.ltx")));
+    checks.push(check_disk_space(appdata));
+
+    let launchable = !checks
+        .iter()
+        .any(|c| !c.passed && c.severity == Severity::Error);
+    HealthReport {
+        game_root: game_root.display().to_string(),
+        checks,
+        launchable,
+    }
+}
+
+fn check_appdata(game_root: &Path, appdata: &Path) -> HealthCheck {
+    // Before the first save appdata doesn't exist yet; it'll be created in the game root.
+    let (dir, detail) = if appdata.is_dir() {
+        (appdata, appdata.display().to_string())
+    } else {
+        (
+            game_root,
+            format!("{} (created on first save)", appdata.display()),
+        )
+    };
+    if fs_util::is_writable_dir(dir) {
+        HealthCheck::pass(CheckId::AppdataWritable, detail)
+    } else {
+        HealthCheck::fail(
+            CheckId::AppdataWritable,
+            Severity::Error,
+            format!("{} is not writable", dir.display()),
+            "Move the game out of Program Files or another protected folder, or give your user write access to it.",
+        )
+    }
+}
+
+fn check_user_defaults(path: &Path) -> HealthCheck {
+    let fix = "Restore user_default.ltx from the Old World Addon download.";
+    let Ok(bytes) = fs::read(path) else {
+        return HealthCheck::fail(
+            CheckId::UserDefaults,
+            Severity::Warning,
+            "user_default.ltx is missing; resetting settings won't work",
+            fix,
+        );
+    };
+
+    // Against empty defaults, so only damage in the file itself counts.
+    // Valueless commands like `default_controls` aren't damage.
+    let report = integrity::check(&bytes, &UserLtx::parse(""));
+    let damaged = report
+        .issues
+        .iter()
+        .filter(|issue| issue.kind != IssueKind::TruncatedLastLine)
+        .count();
+    let commands = UserLtx::parse(&String::from_utf8_lossy(&bytes))
+        .commands()
+        .count();
+
+    if damaged > 0 || commands == 0 {
+        HealthCheck::fail(
+            CheckId::UserDefaults,
+            Severity::Warning,
+            format!(
+                "user_default.ltx is damaged ({} bad line(s), {} command(s))",
+                damaged, commands
+            ),
+            fix,
+        )
+    } else {
+        HealthCheck::pass(
+            CheckId::UserDefaults,
+            format!("user_default.ltx ({} commands)", commands),
+        )
+    }
+}
+
+fn check_disk_space(appdata: &Path) -> HealthCheck {
+    // appdata may not exist yet; measure the nearest folder that does
+    let Some(dir) = appdata.ancestors().find(|p| p.is_dir()) else {
+        return HealthCheck::fail(
+            CheckId::DiskSpace,
+            Severity::Warning,
+            "could not determine free disk space",
+            "Make sure the game drive is mounted.",
+        );
+    };
+    let free = match fs4::available_space(dir) {
+        Ok(free) => free,
+        Err(e) => {
+            return HealthCheck::fail(
+                CheckId::DiskSpace,
+                Severity::Warning,
+                format!("could not determine free disk space: {}", e),
+                "Make sure the game drive is mounted.",
+            );
+        }
+    };
+
+    let detail = format!("{:.1} GB free", free as f64 / (1024.0 * 1024.0 * 1024.0));
+    let fix = "Free up space on the game drive: saves and the shader cache need room.";
+    if free < MIN_FREE_BYTES {
+        HealthCheck::fail(CheckId::DiskSpace, Severity::Error, detail, fix)
+    } else if free < LOW_FREE_BYTES {
+        HealthCheck::fail(CheckId::DiskSpace, Severity::Warning, detail, fix)
+    } else {
+        HealthCheck::pass(CheckId::DiskSpace, detail)
+    }
+}
+
+/// Files with a `.db*` extension (db0, db1, dba, ...), including db/mods.
+fn db_archive_count(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                db_archive_count(&path)
+            } else {
+                let ext = path
+                    .extension()
+                    .map(|e| e.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                usize::from(ext.starts_with("db"))
+            }
+        })
+        .sum()
+}
@@ -1,22 +1,84 @@
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use serde::Serialize;
+
 use crate::config::launcher_config::LauncherConfig;
+use crate::config::user_ltx::UserLtx;
+use crate::fs_util;
 use crate::logging;
 
-/// Launch the game executable with configured arguments.
-pub fn launch_game(game_root: &Path, config: &LauncherConfig) -> Result<(), String> {
+/// How a `LaunchPlan` starts the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LaunchRunner {
+    /// The game exe itself.
+    Direct,
+    /// The Linux custom command, through `sh -c`.
+    Shell,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LaunchWarningId {
+    ExeMissing,
+    /// Linux without a custom command: the .exe is run directly.
+    NoLinuxCommand,
+    /// The custom command doesn't contain `{exe}`.
+    NoExePlaceholder,
+    /// The custom command doesn't contain `{args}`, so the arguments are dropped.
+    ArgsNotPassed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchWarning {
+    pub id: LaunchWarningId,
+    pub message: String,
+    /// `spawn` refuses to run the plan.
+    pub blocking: bool,
+}
+
+/// Everything launching will do, worked out without doing it. `spawn` runs
+/// exactly this, so what the UI shows is what runs.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchPlan {
+    pub runner: LaunchRunner,
+    pub program: PathBuf,
+    /// Arguments after the program.
+    pub argv: Vec<String>,
+    /// Variables set for the game on top of the launcher's own environment.
+    pub env: BTreeMap<String, String>,
+    pub cwd: PathBuf,
+    /// The game exe, whether run directly or through the custom command.
+    pub exe: PathBuf,
+    /// Values substituted into the Linux custom command (`exe`, `args`).
+    pub placeholders: BTreeMap<String, String>,
+    pub warnings: Vec<LaunchWarning>,
+    /// The whole command, quoted for pasting into a shell or support request.
+    pub command_line: String,
+}
+
+/// Work out what launching would run, without running it. `extra_args`
+/// (passed to the launcher, e.g. Steam launch options) go after `custom_args`.
+pub fn plan(game_root: &Path, config: &LauncherConfig, extra_args: &[String]) -> LaunchPlan {
     let exe_name = if config.use_avx {
         "OldWorldDX11AVX.exe"
     } else {
         "OldWorldDX11.exe"
     };
-
     let exe_path = game_root.join("bin").join(exe_name);
+
+    let mut warnings = Vec::new();
     if !exe_path.exists() {
-        logging::log(format!("ERROR: Game executable not found: {}", exe_path.display()));
-        return Err(format!("Game executable not found: {:?}", exe_path));
+        warnings.push(LaunchWarning {
+            id: LaunchWarningId::ExeMissing,
+            message: format!("Game executable not found: {}", exe_path.display()),
+            blocking: true,
+        });
     }
 
     // Build the arguments list (shared between Windows and Linux)
@@ -40,84 +102,135 @@ pub fn launch_game(game_root: &Path, config: &LauncherConfig) -> Result<(), Stri
         }
     }
 
-    // Platform-specific launch
-    if cfg!(target_os = "linux") {
-        launch_linux(game_root, &exe_path, config, &args)
-    } else {
-        launch_windows(game_root, &exe_path, &args)
+    // Arguments passed through from the launcher's own command line
+    args.extend(extra_args.iter().cloned());
+
+    let custom_cmd = config
+        .linux_custom_command
+        .as_deref()
+        .filter(|c| !c.trim().is_empty());
+    let (runner, program, argv, placeholders) = match custom_cmd {
+        // Linux: a custom command replaces direct execution
+        Some(custom_cmd) if cfg!(target_os = "linux") => {
+            if !custom_cmd.contains("{exe}") {
+                warnings.push(LaunchWarning {
+                    id: LaunchWarningId::NoExePlaceholder,
+                    message: "The custom launch command doesn't contain {exe}".to_string(),
+                    blocking: false,
+                });
+            }
+            if !custom_cmd.contains("{args}") && !args.is_empty() {
+                warnings.push(LaunchWarning {
+                    id: LaunchWarningId::ArgsNotPassed,
+                    message: format!(
+                        "The custom launch command doesn't contain {{args}}, so {} won't reach the game",
+                        args.join(" ")
+                    ),
+                    blocking: false,
+                });
+            }
+
+            // Replace placeholders: {exe} with exe path, {args} with the
+            // args quoted for sh, so forwarded Steam options with spaces or
+            // shell characters arrive as they were passed
+            let quoted: Vec<String> = args.iter().map(|a| shell_quote(a)).collect();
+            let placeholders = BTreeMap::from([
+                ("exe".to_string(), exe_path.to_string_lossy().into_owned()),
+                ("args".to_string(), quoted.join(" ")),
+            ]);
+            let full_command = custom_cmd
+                .replace("{exe}", &placeholders["exe"])
+                .replace("{args}", &placeholders["args"]);
+            (
+                LaunchRunner::Shell,
+                PathBuf::from("sh"),
+                vec!["-c".to_string(), full_command],
+                placeholders,
+            )
+        }
+        _ => {
+            if cfg!(target_os = "linux") {
+                warnings.push(LaunchWarning {
+                    id: LaunchWarningId::NoLinuxCommand,
+                    message: "No custom launch command set; running the .exe directly will likely fail. Set one, e.g. wine {exe} {args}".to_string(),
+                    blocking: false,
+                });
+            }
+            (LaunchRunner::Direct, exe_path.clone(), args, BTreeMap::new())
+        }
+    };
+
+    let command_line = std::iter::once(program.to_string_lossy().into_owned())
+        .chain(argv.iter().cloned())
+        .map(|arg| quote(&arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    LaunchPlan {
+        runner,
+        program,
+        argv,
+        env: BTreeMap::new(),
+        cwd: game_root.to_path_buf(),
+        exe: exe_path,
+        placeholders,
+        warnings,
+        command_line,
     }
 }
 
-/// Windows launch: directly execute the .exe
-fn launch_windows(game_root: &Path, exe_path: &Path, args: &[String]) -> Result<(), String> {
-    logging::log("--- Game Launch (Windows) ---");
-    logging::log(format!("Exe:  {}", exe_path.display()));
-    logging::log(format!("Args: {:?}", args));
-    logging::log(format!("CWD:  {}", game_root.display()));
+/// Spawn the process described by `plan`, unless one of its warnings blocks it.
+pub fn spawn(plan: &LaunchPlan) -> Result<(), String> {
+    let platform = if cfg!(target_os = "linux") { "Linux" } else { "Windows" };
+    logging::log(format!("--- Game Launch ({}) ---", platform));
+    logging::log(format!("Command: {}", plan.command_line));
+    logging::log(format!("CWD:  {}", plan.cwd.display()));
+    for warning in &plan.warnings {
+        logging::log(format!("WARNING: {}", warning.message));
+    }
+    if let Some(blocking) = plan.warnings.iter().find(|w| w.blocking) {
+        logging::log(format!("ERROR: {}", blocking.message));
+        return Err(blocking.message.clone());
+    }
 
-    let mut cmd = Command::new(exe_path);
-    cmd.args(args).current_dir(game_root);
+    let mut cmd = Command::new(&plan.program);
+    cmd.args(&plan.argv).envs(&plan.env).current_dir(&plan.cwd);
 
     cmd.spawn().map_err(|e| {
         logging::log(format!("ERROR: Failed to launch: {}", e));
-        format!("Failed to launch {:?}: {}", exe_path, e)
+        match (plan.runner, cfg!(target_os = "linux")) {
+            (LaunchRunner::Shell, _) => format!("Failed to launch custom command: {}", e),
+            (LaunchRunner::Direct, true) => format!(
+                "Failed to launch {:?}: {}. On Linux, set a custom launch command (e.g., wine {{exe}} {{args}})",
+                plan.program, e
+            ),
+            (LaunchRunner::Direct, false) => format!("Failed to launch {:?}: {}", plan.program, e),
+        }
     })?;
 
     logging::log("Game process spawned successfully.");
     Ok(())
 }
 
-/// Linux launch: use custom command if provided, otherwise try direct execution
-fn launch_linux(game_root: &Path, exe_path: &Path, config: &LauncherConfig, args: &[String]) -> Result<(), String> {
-    logging::log("--- Game Launch (Linux) ---");
-
-    if let Some(custom_cmd) = &config.linux_custom_command {
-        if !custom_cmd.trim().is_empty() {
-            // User has provided a custom command
-            // Replace placeholders: {exe} with exe path, {args} with space-joined args
-            let exe_str = exe_path.to_string_lossy();
-            let args_str = args.join(" ");
-
-            let full_command = custom_cmd
-                .replace("{exe}", &exe_str)
-                .replace("{args}", &args_str);
-
-            logging::log(format!("Custom command: {}", full_command));
-            logging::log(format!("CWD: {}", game_root.display()));
-
-            // Execute via shell
-            let mut cmd = Command::new("sh");
-            cmd.arg("-c").arg(&full_command).current_dir(game_root);
-
-            cmd.spawn().map_err(|e| {
-                logging::log(format!("ERROR: Failed to launch custom command: {}", e));
-                format!("Failed to launch custom command: {}", e)
-            })?;
-
-            logging::log("Game process spawned successfully (custom command).");
-            return Ok(());
+/// Quote an argument for display when it has spaces or quotes in it.
+fn quote(arg: &str) -> String {
+    if cfg!(windows) {
+        if !arg.is_empty() && !arg.contains(|c: char| c.is_whitespace() || c == '"') {
+            return arg.to_string();
         }
+        format!("\"{}\"", arg.replace('"', "\\\""))
+    } else {
+        shell_quote(arg)
     }
+}
 
-    // Fallback: try direct execution (unlikely to work for Windows exe)
-    logging::log(format!("Exe:  {}", exe_path.display()));
-    logging::log(format!("Args: {:?}", args));
-    logging::log(format!("CWD:  {}", game_root.display()));
-    logging::log("WARNING: No linux_custom_command set, attempting direct execution (may fail)");
-
-    let mut cmd = Command::new(exe_path);
-    cmd.args(args).current_dir(game_root);
-
-    cmd.spawn().map_err(|e| {
-        logging::log(format!("ERROR: Failed to launch: {}", e));
-        format!(
-            "Failed to launch {:?}: {}. On Linux, set a custom launch command (e.g., wine {{exe}} {{args}})",
-            exe_path, e
-        )
-    })?;
-
-    logging::log("Game process spawned successfully.");
-    Ok(())
+/// Quote an argument for `sh`, leaving plain ones (`-smap4096`) as they are.
+fn shell_quote(arg: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_./:=+,@%".contains(c);
+    if !arg.is_empty() && arg.chars().all(plain) {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', "'\\''"))
 }
 
 /// Delete the shader cache directory and return the number of bytes freed.
@@ -144,14 +257,17 @@ pub fn reset_user_ltx(appdata_path: &Path, game_root: &Path) -> Result<(), Strin
         // Fallback: if user_default.ltx is missing, just delete user.ltx
         logging::log("user_default.ltx not found, falling back to delete");
         if user_path.exists() {
+            fs_util::set_read_only(&user_path, false)?;
             fs::remove_file(&user_path)
                 .map_err(|e| format!("Failed to delete user.ltx: {}", e))?;
         }
         return Ok(());
     }
 
-    fs::copy(&default_path, &user_path)
-        .map_err(|e| format!("Failed to copy user_default.ltx to user.ltx: {}", e))?;
+    let content = fs::read(&default_path)
+        .map_err(|e| format!("Failed to read user_default.ltx: {}", e))?;
+    UserLtx::write_file(appdata_path, content)
+        .map_err(|e| format!("Failed to reset user.ltx: {}", e))?;
 
     logging::log(format!(
         "OWA: Reset user.ltx from user_default.ltx ({})",
@@ -1,2 +1,5 @@
+pub mod discovery;
+pub mod health;
 pub mod launcher;
 pub mod paths;
+pub mod provision;
@@ -2,6 +2,7 @@ use std::env;
 use std::path::{Path, PathBuf};
 
 use crate::logging;
+use crate::storage::StorageLocation;
 
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -10,6 +11,8 @@ pub struct GamePaths {
     pub appdata: PathBuf,
     pub bin: PathBuf,
     pub launcher_dir: PathBuf,
+    /// Where launcher_config.json, the settings journal and the log live.
+    pub storage: StorageLocation,
 }
 
 impl GamePaths {
@@ -19,7 +22,10 @@ impl GamePaths {
     /// 1. `OWL_GAME_ROOT` environment variable (for dev/testing)
     /// 2. `config_game_root` from launcher_config.json (user override)
     /// 3. Relative to launcher executable (parent directory)
-    pub fn resolve(config_game_root: Option<&str>) -> Result<Self, String> {
+    pub fn resolve(
+        config_game_root: Option<&str>,
+        storage: StorageLocation,
+    ) -> Result<Self, String> {
         logging::log("--- Path Resolution ---");
 
         let launcher_dir = Self::get_launcher_dir()?;
@@ -58,9 +64,29 @@ impl GamePaths {
             appdata,
             bin,
             launcher_dir,
+            storage,
         })
     }
 
+    /// Check that `dir` looks like a game install before switching to it.
+    pub fn validate_game_root(dir: &str) -> Result<PathBuf, String> {
+        let dir = dir.trim();
+        if dir.is_empty() {
+            return Err("No game folder given".to_string());
+        }
+        let path = PathBuf::from(dir);
+        if !path.is_dir() {
+            return Err(format!("'{}' is not a folder", dir));
+        }
+        if !path.join("gamedata").is_dir() {
+            return Err(format!(
+                "'{}' doesn't look like the game folder (no gamedata folder)",
+                dir
+            ));
+        }
+        Ok(path)
+    }
+
     fn get_launcher_dir() -> Result<PathBuf, String> {
         let exe_path = env::current_exe()
             .map_err(|e| format!("Failed to get launcher executable path: {}", e))?;
@@ -134,14 +160,14 @@ impl GamePaths {
     }
 
     /// Resolve appdata directory.
-    fn resolve_appdata(game_root: &Path) -> PathBuf {
+    pub fn resolve_appdata(game_root: &Path) -> PathBuf {
         let local_appdata = game_root.join("appdata");
         if local_appdata.is_dir() {
             return local_appdata;
         }
 
         logging::log(format!(
-            "WARNING: appdata directory does not exist at {}",
+            "WARNING: appdata directory does not exist at {} (will be provisioned on first save)",
             local_appdata.display()
         ));
         local_appdata
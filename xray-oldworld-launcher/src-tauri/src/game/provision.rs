@@ -0,0 +1,87 @@
+use std::fs;
+use std::path::Path;
+
+use crate::config::defaults_sync;
+use crate::config::user_ltx::UserLtx;
+use crate::fs_util;
+use crate::game::paths::GamePaths;
+use crate::logging;
+
+/// Directories the engine expects under appdata. It creates them itself on
+/// first launch, but the launcher is used before that.
+const APPDATA_SUBDIRS: &[&str] = &["logs", "savedgames", "screenshots"];
+
+/// What first-run provisioning did.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvisionReport {
+    /// Directories that were created.
+    pub created_dirs: Vec<String>,
+    /// user.ltx was created from user_default.ltx.
+    pub seeded_user_ltx: bool,
+    /// Number of commands copied from user_default.ltx.
+    pub seeded_commands: usize,
+}
+
+impl ProvisionReport {
+    pub fn is_empty(&self) -> bool {
+        self.created_dirs.is_empty() && !self.seeded_user_ltx
+    }
+}
+
+/// Make sure appdata exists and user.ltx holds the OWA defaults before the
+/// launcher writes to it. Without this, the first save from a fresh install
+/// would produce a user.ltx containing only the changed keys.
+pub fn ensure_provisioned(paths: &GamePaths) -> Result<ProvisionReport, String> {
+    let mut report = ProvisionReport::default();
+
+    create_dir(&paths.appdata, &mut report)?;
+    for sub in APPDATA_SUBDIRS {
+        create_dir(&paths.appdata.join(sub), &mut report)?;
+    }
+
+    let user_path = paths.appdata.join("user.ltx");
+    let default_path = UserLtx::defaults_path(&paths.game_root);
+    if !user_path.exists() && default_path.exists() {
+        // Read and write rather than fs::copy so a read-only user_default.ltx
+        // doesn't produce a read-only user.ltx.
+        let content = fs::read_to_string(&default_path)
+            .map_err(|e| format!("Failed to read {:?}: {}", default_path, e))?;
+        fs_util::write_atomic(&user_path, &content, false)
+            .map_err(|e| format!("Failed to create user.ltx: {}", e))?;
+        defaults_sync::record_snapshot(&paths.appdata, &paths.game_root)?;
+
+        report.seeded_user_ltx = true;
+        report.seeded_commands = UserLtx::parse(&content).commands().count();
+        logging::log(format!(
+            "OWA: Seeded user.ltx from user_default.ltx ({} commands)",
+            report.seeded_commands
+        ));
+    }
+
+    if !report.is_empty() {
+        logging::log(format!("Provisioning: {:?}", report));
+    }
+    Ok(report)
+}
+
+fn create_dir(path: &Path, report: &mut ProvisionReport) -> Result<(), String> {
+    if path.is_dir() {
+        return Ok(());
+    }
+
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o755);
+    }
+    builder
+        .create(path)
+        .map_err(|e| format!("Failed to create directory {:?}: {}", path, e))?;
+
+    logging::log(format!("Created directory: {}", path.display()));
+    report.created_dirs.push(path.display().to_string());
+    Ok(())
+}
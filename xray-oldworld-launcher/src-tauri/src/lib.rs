@@ -1,52 +1,101 @@
+mod cli;
 mod config;
+mod fs_util;
 mod game;
 mod logging;
+mod storage;
 
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::RwLock;
+use std::time::Duration;
 
-use config::launcher_config::LauncherConfig;
+use tauri::{Emitter, Manager};
+
+use config::axr_options;
+use config::change_journal::{self, ChangeOrigin, JournalRecord};
+use config::command_scan::{self, CommandScan};
+use config::console_schema::SchemaGroup;
+use config::defaults_diff::{self, DefaultsDiff};
+use config::defaults_sync::{self, DefaultsUpdate};
+use config::file_stamp::{self, FileStamp};
+use config::integrity::{self, IntegrityReport, RepairReport};
+use config::config_layers::{self, EffectiveConfig, LayeredConfig, ModDefaults};
+use config::launcher_config::{ConfigRecovery, Installation, LauncherConfig};
+use config::options_view::{self, CommentLine, OptionEntry};
+use config::save_merge::{self, OptionsBase, SaveConflict};
+use config::save_preview::{self, SavePreview};
+use config::settings_import::{self, ImportPreview};
+use config::settings_share;
+use config::transaction::ConfigTransaction;
 use config::user_ltx::UserLtx;
+use game::discovery::InstallCandidate;
+use game::health::HealthReport;
+use game::launcher::LaunchPlan;
 use game::paths::GamePaths;
+use game::provision::{self, ProvisionReport};
+use storage::{StorageLocation, StorageMode};
 
 // -- Application State --
 
 struct AppState {
+    storage: StorageLocation,
     paths: RwLock<Option<GamePaths>>,
-    launcher_config: RwLock<LauncherConfig>,
+    launcher_config: RwLock<LayeredConfig>,
+    /// user.ltx as last shown to the UI, for detecting external rewrites on save.
+    options_base: RwLock<Option<OptionsBase>>,
+    /// Problems found loading launcher_config.json at startup.
+    config_recovery: RwLock<Option<ConfigRecovery>>,
+    /// Launcher arguments forwarded to the game (see `cli::parse`).
+    game_args: Vec<String>,
+    /// Why auto-launch didn't start the game, for the window to show.
+    auto_launch_error: RwLock<Option<String>>,
 }
 
 impl AppState {
-    fn new() -> Self {
+    fn new(storage: StorageLocation, game_args: Vec<String>) -> Self {
         AppState {
+            storage,
             paths: RwLock::new(None),
-            launcher_config: RwLock::new(LauncherConfig::default()),
+            launcher_config: RwLock::new(LayeredConfig::new(Default::default(), Default::default())),
+            options_base: RwLock::new(None),
+            config_recovery: RwLock::new(None),
+            game_args,
+            auto_launch_error: RwLock::new(None),
         }
     }
 
-    /// Initialize paths and load launcher config.
+    /// Initialize paths and load launcher config. `game_root` (from
+    /// `--game-root`) overrides the configured folder for this run.
     /// Logging must already be initialized before calling this.
-    fn initialize(&self) -> Result<(), String> {
+    fn initialize(&self, args: &[String], game_root: Option<&Path>) -> Result<(), String> {
         logging::log("Initializing Old World Launcher...");
 
-        let launcher_dir = std::env::current_exe()
-            .map_err(|e| format!("Failed to get exe path: {}", e))?
-            .parent()
-            .map(|p| p.to_path_buf())
-            .ok_or_else(|| "Failed to get launcher directory".to_string())?;
-
-     
-
-        let config = LauncherConfig::load(&launcher_dir);
-        logging::log(format!("Launcher config loaded: game_root={:?}", config.game_root));
+        let (user, recovery) = LauncherConfig::load_checked(&self.storage.config_dir);
+        *self.config_recovery.write().map_err(|e| e.to_string())? = recovery;
+        let mut overrides = config_layers::overrides_from_env_and_args(std::env::vars(), args);
+        if let Some(root) = game_root {
+            overrides.insert(
+                "gameRoot".to_string(),
+                serde_json::Value::String(root.to_string_lossy().into_owned()),
+            );
+        }
+        let config = LayeredConfig::new(user, overrides);
+        let game_root = config.effective().game_root.clone();
+        logging::log(format!("Launcher config loaded: game_root={:?}", game_root));
+        // Set before resolving paths so the config can still be saved if that fails
+        *self.launcher_config.write().map_err(|e| e.to_string())? = config;
 
         // Resolve paths using config's game_root
-        let paths = GamePaths::resolve(config.game_root.as_deref())?;
+        let paths = GamePaths::resolve(game_root.as_deref(), self.storage.clone())?;
+        self.launcher_config
+            .write()
+            .map_err(|e| e.to_string())?
+            .set_mod_defaults(ModDefaults::load(&paths.game_root));
 
         logging::log("Initialization complete.");
 
         *self.paths.write().map_err(|e| e.to_string())? = Some(paths);
-        *self.launcher_config.write().map_err(|e| e.to_string())? = config;
 
         Ok(())
     }
@@ -58,14 +107,95 @@ impl AppState {
             .clone()
             .ok_or_else(|| "Game paths not initialized".to_string())
     }
+
+    /// Take `user` as the new options base after the launcher wrote it, so
+    /// neither the watcher nor the next save mistakes that write for an
+    /// external change.
+    fn record_options_base(&self, paths: &GamePaths, user: &UserLtx) -> Result<(), String> {
+        *self.options_base.write().map_err(|e| e.to_string())? = Some(OptionsBase {
+            stamp: FileStamp::read(&UserLtx::file_path(&paths.appdata)),
+            values: user.get_all(),
+        });
+        Ok(())
+    }
 }
 
 // -- Data Transfer Objects --
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 struct OptionsState {
+    /// Flat command -> value map (last occurrence wins), kept for simple lookups.
     user_ltx: HashMap<String, String>,
+    /// axr_options.ltx values keyed by option path.
+    axr_options: HashMap<String, String>,
+    /// Ordered entries with source, default value and schema validity.
+    entries: Vec<OptionEntry>,
+    comments: Vec<CommentLine>,
+    user_ltx_path: String,
+    /// user.ltx modification time in milliseconds since the Unix epoch.
+    user_ltx_modified: Option<u64>,
+    /// No user.ltx yet; values shown come from user_default.ltx.
+    using_defaults: bool,
+    /// Set when user.ltx looks damaged; the UI offers `repair_user_ltx`.
+    integrity: Option<IntegrityReport>,
+    /// user.ltx is read-only, so the game can't overwrite it.
+    settings_locked: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SaveResult {
+    /// False when conflicts prevented the write.
+    saved: bool,
+    provisioned: ProvisionReport,
+    /// user.ltx changed on disk since get_options and the changes were merged onto it.
+    merged_external_changes: bool,
+    conflicts: Vec<SaveConflict>,
+    /// Set for dry runs: what the save would do. Nothing is written.
+    preview: Option<SavePreview>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsExport {
+    /// Compact copy-pasteable form (`OWL1:...`).
+    code: String,
+    /// Settings file written, if a path was given.
+    path: Option<String>,
+    commands: usize,
+    axr_options: usize,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UndoResult {
+    /// The undo/redo record written, or None if there was nothing to do.
+    record: Option<JournalRecord>,
+    /// Entry keys left alone because they changed since the original record.
+    skipped: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InstallationList {
+    installations: Vec<Installation>,
+    active: Option<String>,
+}
+
+impl From<&LauncherConfig> for InstallationList {
+    fn from(config: &LauncherConfig) -> Self {
+        InstallationList {
+            installations: config.installations.clone(),
+            active: config.active_installation.clone(),
+        }
+    }
+}
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct UserLtxChangedEvent {
+    path: String,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -83,6 +213,9 @@ struct OptionChange {
 enum OptionStorage {
     #[serde(rename = "userLtx")]
     UserLtx { cmd: String },
+    /// Stored in axr_options.ltx under the option's `path`.
+    #[serde(rename = "axrOptions")]
+    AxrOptions,
 }
 
 
@@ -101,66 +234,496 @@ fn get_options(state: tauri::State<'_, AppState>) -> Result<OptionsState, String
     logging::log("IPC: get_options called");
     let paths = state.get_paths()?;
 
-    let user = UserLtx::load_with_fallback(&paths.appdata, &paths.game_root);
-    let all = user.get_all();
-    logging::log(format!("IPC: get_options returning {} commands", all.len()));
+    let user_path = UserLtx::file_path(&paths.appdata);
+    let stamp = FileStamp::read(&user_path);
+    let defaults = UserLtx::load_defaults(&paths.game_root);
+    let user = user_path.exists().then(|| UserLtx::load(&paths.appdata));
+
+    let all = user.as_ref().unwrap_or(&defaults).get_all();
+    let (entries, comments) = options_view::build(user.as_ref(), &defaults);
+    logging::log(format!(
+        "IPC: get_options returning {} commands ({} entries)",
+        all.len(),
+        entries.len()
+    ));
+
+    let user_ltx_modified = stamp
+        .as_ref()
+        .and_then(|s| s.mtime)
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64);
+
+    *state.options_base.write().map_err(|e| e.to_string())? = Some(OptionsBase {
+        stamp,
+        values: all.clone(),
+    });
+
+    let axr = axr_options::load_values(&paths.game_root);
+
+    let integrity = user
+        .is_some()
+        .then(|| std::fs::read(&user_path).ok())
+        .flatten()
+        .map(|bytes| integrity::check(&bytes, &defaults))
+        .filter(|report| !report.is_ok());
+    if let Some(report) = &integrity {
+        logging::log(format!(
+            "WARNING: user.ltx failed integrity checks: {:?}",
+            report.issues.iter().map(|i| i.kind).collect::<Vec<_>>()
+        ));
+    }
 
     Ok(OptionsState {
         user_ltx: all,
+        axr_options: axr,
+        entries,
+        comments,
+        user_ltx_path: user_path.display().to_string(),
+        user_ltx_modified,
+        using_defaults: user.is_none(),
+        integrity,
+        settings_locked: fs_util::is_read_only(&user_path),
     })
 }
 
+/// Save pending option changes to user.ltx and axr_options.ltx.
+///
+/// If the game rewrote user.ltx since `get_options`, the changes are merged
+/// onto the new file. Changes to commands the game also changed are returned
+/// as conflicts and nothing is written, unless `force` is set.
+///
+/// With `dry_run`, nothing is written and the result carries a preview of
+/// the resulting file instead. `origin` tags the change journal (default UI).
 #[tauri::command]
 fn save_options(
     changes: Vec<OptionChange>,
+    force: Option<bool>,
+    dry_run: Option<bool>,
+    origin: Option<ChangeOrigin>,
     state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
-    let paths = state.get_paths()?;
+) -> Result<SaveResult, String> {
+    save_settings(
+        &state,
+        changes,
+        None,
+        force.unwrap_or(false),
+        dry_run.unwrap_or(false),
+        origin.unwrap_or(ChangeOrigin::Ui),
+    )
+}
 
-    let mut user = UserLtx::load(&paths.appdata);
-    let mut user_dirty = false;
+/// Apply option changes and the launcher config together: either every
+/// file is written or none are.
+#[tauri::command]
+fn apply_settings(
+    changes: Vec<OptionChange>,
+    launcher_config: Option<LauncherConfig>,
+    force: Option<bool>,
+    dry_run: Option<bool>,
+    origin: Option<ChangeOrigin>,
+    state: tauri::State<'_, AppState>,
+) -> Result<SaveResult, String> {
+    save_settings(
+        &state,
+        changes,
+        launcher_config,
+        force.unwrap_or(false),
+        dry_run.unwrap_or(false),
+        origin.unwrap_or(ChangeOrigin::Ui),
+    )
+}
 
+fn save_settings(
+    state: &AppState,
+    changes: Vec<OptionChange>,
+    launcher_config: Option<LauncherConfig>,
+    force: bool,
+    dry_run: bool,
+    origin: ChangeOrigin,
+) -> Result<SaveResult, String> {
+    let paths = state.get_paths()?;
+
+    // First save on a fresh install: create appdata and seed user.ltx from
+    // the OWA defaults so the changes land on top of a complete file.
+    // A dry run previews against the defaults the seed would write.
+    let user_path = UserLtx::file_path(&paths.appdata);
+    let existed = user_path.exists();
+    let (provisioned, mut user) = if dry_run {
+        let user = if user_path.exists() {
+            UserLtx::load(&paths.appdata)
+        } else {
+            UserLtx::load_defaults(&paths.game_root)
+        };
+        (ProvisionReport::default(), user)
+    } else {
+        let provisioned = provision::ensure_provisioned(&paths)?;
+        (provisioned, UserLtx::load(&paths.appdata))
+    };
+    let mut cmd_changes: Vec<(String, String)> = Vec::new();
+    let mut axr_changes: Vec<(String, String)> = Vec::new();
     for change in changes {
         match change.storage {
-            OptionStorage::UserLtx { cmd } => {
-                user.set(&cmd, &change.value);
-                user_dirty = true;
-            }
+            OptionStorage::UserLtx { cmd } => cmd_changes.push((cmd, change.value)),
+            OptionStorage::AxrOptions => axr_changes.push((change.path, change.value)),
         }
     }
 
+    let base = state.options_base.read().map_err(|e| e.to_string())?.clone();
+    // A user.ltx this save just seeded isn't an external change.
+    let merged_external_changes = existed
+        && base
+            .as_ref()
+            .is_some_and(|b| b.changed_on_disk(&user_path));
 
-    if user_dirty {
-        user.save(&paths.appdata)?;
+    if merged_external_changes {
+        let conflicts = base
+            .as_ref()
+            .map(|b| save_merge::find_conflicts(b, &user, &cmd_changes))
+            .unwrap_or_default();
+        logging::log(format!(
+            "user.ltx changed on disk since it was loaded (mtime {:?} -> {:?}, {} conflicting keys)",
+            base.as_ref().and_then(|b| b.stamp.as_ref()).and_then(|s| s.mtime),
+            file_stamp::modified(&user_path),
+            conflicts.len()
+        ));
+        if !conflicts.is_empty() && !force {
+            let preview = dry_run.then(|| preview_changes(&user, &cmd_changes));
+            return Ok(SaveResult {
+                saved: false,
+                provisioned,
+                merged_external_changes: false,
+                conflicts,
+                preview,
+            });
+        }
     }
 
-    Ok(())
+    if dry_run {
+        return Ok(SaveResult {
+            saved: false,
+            provisioned,
+            merged_external_changes,
+            conflicts: Vec::new(),
+            preview: Some(preview_changes(&user, &cmd_changes)),
+        });
+    }
+
+    let mut tx = ConfigTransaction::new();
+    let before = user.clone();
+
+    if !cmd_changes.is_empty() {
+        for (cmd, value) in &cmd_changes {
+            user.set(cmd, value);
+        }
+        tx.stage(&user_path, user.render(), true);
+    }
+
+    axr_options::stage_changes(&mut tx, &paths.game_root, &axr_changes)?;
+
+    let launcher_fields = match &launcher_config {
+        Some(config) => Some(
+            state
+                .launcher_config
+                .read()
+                .map_err(|e| e.to_string())?
+                .user_fields_for(config),
+        ),
+        None => None,
+    };
+    if let Some(fields) = &launcher_fields {
+        tx.stage(
+            &LauncherConfig::file_path(&paths.storage.config_dir),
+            LauncherConfig::fields_to_json(fields)?,
+            false,
+        );
+    }
+
+    // Locked like `UserLtx::write_file`: unlock for the write, lock again.
+    if !tx.is_empty() {
+        fs_util::with_unlocked(&user_path, || tx.commit())?;
+    }
+    change_journal::record(&paths.storage.config_dir, &paths.game_root, origin, &before, &user);
+
+    if let Some(fields) = launcher_fields {
+        state
+            .launcher_config
+            .write()
+            .map_err(|e| e.to_string())?
+            .set_user(fields);
+        *state.config_recovery.write().map_err(|e| e.to_string())? = None;
+    }
+
+    state.record_options_base(&paths, &user)?;
+
+    Ok(SaveResult {
+        saved: true,
+        provisioned,
+        merged_external_changes,
+        conflicts: Vec::new(),
+        preview: None,
+    })
 }
 
+fn preview_changes(user: &UserLtx, changes: &[(String, String)]) -> SavePreview {
+    let mut after = user.clone();
+    for (cmd, value) in changes {
+        after.set(cmd, value);
+    }
+    save_preview::build(user, &after, changes)
+}
 
 #[tauri::command]
-fn get_launcher_config(state: tauri::State<'_, AppState>) -> Result<LauncherConfig, String> {
+fn provision_appdata(state: tauri::State<'_, AppState>) -> Result<ProvisionReport, String> {
+    let paths = state.get_paths()?;
+    provision::ensure_provisioned(&paths)
+}
+
+/// Effective launcher config, with the layer each field came from and
+/// whether the mod locked it.
+#[tauri::command]
+fn get_launcher_config(state: tauri::State<'_, AppState>) -> Result<EffectiveConfig, String> {
     state
         .launcher_config
         .read()
-        .map(|c| c.clone())
+        .map(|c| c.view())
         .map_err(|e| e.to_string())
 }
 
+/// Problems found loading launcher_config.json at startup, if any: fields
+/// reset to defaults and where the original file was preserved.
+#[tauri::command]
+fn get_config_recovery(
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<ConfigRecovery>, String> {
+    Ok(state.config_recovery.read().map_err(|e| e.to_string())?.clone())
+}
+
+/// Why auto-launch opened the window instead of starting the game, if it did.
+#[tauri::command]
+fn get_auto_launch_error(state: tauri::State<'_, AppState>) -> Result<Option<String>, String> {
+    Ok(state.auto_launch_error.read().map_err(|e| e.to_string())?.clone())
+}
+
 #[tauri::command]
 fn save_launcher_config(
     config: LauncherConfig,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
-    let paths = state.get_paths()?;
-    config.save(&paths.launcher_dir)?;
-    *state
+    let mut layered = state.launcher_config.write().map_err(|e| e.to_string())?;
+    let fields = layered.user_fields_for(&config);
+    LauncherConfig::save_fields(&state.storage.config_dir, &fields)?;
+    layered.set_user(fields);
+    // The file on disk is whole again
+    *state.config_recovery.write().map_err(|e| e.to_string())? = None;
+    Ok(())
+}
+
+/// Switch to another game folder at runtime: validate it, resolve paths for
+/// it, persist it and swap state in one step. Also the way out when startup
+/// couldn't resolve a game root. Emits `game-root-changed` with the new paths.
+#[tauri::command]
+fn set_game_root(
+    game_root: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<GamePaths, String> {
+    let root = GamePaths::validate_game_root(&game_root)?;
+    let value = root.display().to_string();
+    // A folder picked by hand isn't the selected installation any more;
+    // leaving it active would repoint that entry at the new folder.
+    switch_game_root(&app, &state, &root, |layered| {
+        Ok(layered.user_fields_with(|config| {
+            config.game_root = Some(value);
+            config.active_installation = None;
+        }))
+    })
+}
+
+#[tauri::command]
+fn list_installations(state: tauri::State<'_, AppState>) -> Result<InstallationList, String> {
+    let layered = state.launcher_config.read().map_err(|e| e.to_string())?;
+    Ok(InstallationList::from(layered.effective()))
+}
+
+/// Add an installation, or replace the one with the same name. Replacing the
+/// active one switches to its new settings right away.
+#[tauri::command]
+fn add_installation(
+    installation: Installation,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<InstallationList, String> {
+    let mut installation = installation;
+    installation.name = installation.name.trim().to_string();
+    if installation.name.is_empty() {
+        return Err("Installation name is empty".to_string());
+    }
+    let root = GamePaths::validate_game_root(&installation.game_root)?;
+    installation.game_root = root.display().to_string();
+
+    let name = installation.name.clone();
+    let edit = move |config: &mut LauncherConfig| {
+        match config.installations.iter_mut().find(|i| i.name == installation.name) {
+            Some(existing) => *existing = installation,
+            None => config.installations.push(installation),
+        }
+    };
+
+    let active = state
         .launcher_config
-        .write()
-        .map_err(|e| e.to_string())? = config;
+        .read()
+        .map_err(|e| e.to_string())?
+        .effective()
+        .active_installation
+        .clone();
+    if active.as_deref() == Some(name.as_str()) {
+        switch_game_root(&app, &state, &root, |layered| {
+            layered.user_fields_selecting(&name, edit)
+        })?;
+    } else {
+        update_launcher_config(&state, edit)?;
+    }
+    logging::log(format!("Installation '{}' saved", name));
+    list_installations(state)
+}
+
+/// Remove an installation entry. Removing the active one leaves the current
+/// game folder and settings in place.
+#[tauri::command]
+fn remove_installation(
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<InstallationList, String> {
+    update_launcher_config(&state, |config| {
+        config.installations.retain(|i| i.name != name);
+        if config.active_installation.as_deref() == Some(name.as_str()) {
+            config.active_installation = None;
+        }
+    })?;
+    list_installations(state)
+}
+
+/// Switch to a registered installation and its launch settings.
+#[tauri::command]
+fn select_installation(
+    name: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<GamePaths, String> {
+    let entry = state
+        .launcher_config
+        .read()
+        .map_err(|e| e.to_string())?
+        .effective()
+        .installation(&name)
+        .cloned()
+        .ok_or_else(|| format!("No installation named '{}'", name))?;
+    let root = GamePaths::validate_game_root(&entry.game_root)?;
+    switch_game_root(&app, &state, &root, |layered| {
+        layered.user_fields_selecting(&entry.name, |_| {})
+    })
+}
+
+/// Resolve paths for `root`, persist the launcher config fields `user_fields`
+/// builds and swap paths, config and options base together, then tell the UI.
+fn switch_game_root(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    root: &Path,
+    user_fields: impl FnOnce(
+        &LayeredConfig,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, String>,
+) -> Result<GamePaths, String> {
+    let paths = GamePaths::resolve(Some(&root.to_string_lossy()), state.storage.clone())?;
+    if paths.game_root != root {
+        return Err(format!(
+            "OWL_GAME_ROOT is set to '{}' and takes priority over the game folder setting",
+            paths.game_root.display()
+        ));
+    }
+    let mod_defaults = ModDefaults::load(&paths.game_root);
+
+    {
+        // Held together so no command sees paths of one install with the
+        // config or options base of another.
+        let mut current_paths = state.paths.write().map_err(|e| e.to_string())?;
+        let mut layered = state.launcher_config.write().map_err(|e| e.to_string())?;
+        let mut options_base = state.options_base.write().map_err(|e| e.to_string())?;
+
+        let fields = user_fields(&layered)?;
+        LauncherConfig::save_fields(&state.storage.config_dir, &fields)?;
+
+        // Picking a folder here is explicit, so it beats `--game-root` or
+        // `--config gameRoot=...` given at startup.
+        if layered.clear_override("gameRoot") {
+            logging::log("Dropped the game root override for the rest of this run");
+        }
+        layered.set_user(fields);
+        layered.set_mod_defaults(mod_defaults);
+        *current_paths = Some(paths.clone());
+        // The base belonged to the old install's user.ltx
+        *options_base = None;
+    }
+
+    logging::log(format!("Game root switched to {}", paths.game_root.display()));
+    if let Err(e) = app.emit("game-root-changed", &paths) {
+        logging::log(format!("ERROR: Failed to emit game-root-changed: {}", e));
+    }
+    Ok(paths)
+}
+
+/// Persist a launcher-made change to the config without touching paths.
+fn update_launcher_config(
+    state: &AppState,
+    edit: impl FnOnce(&mut LauncherConfig),
+) -> Result<(), String> {
+    let mut layered = state.launcher_config.write().map_err(|e| e.to_string())?;
+    let fields = layered.user_fields_with(edit);
+    LauncherConfig::save_fields(&state.storage.config_dir, &fields)?;
+    layered.set_user(fields);
     Ok(())
 }
 
+/// Scan Steam libraries, Wine/Proton prefixes, Lutris/Heroic, the home dir
+/// and mounted drives for game installs, best match first. The scan walks
+/// whole drives, so it runs on a blocking thread.
+#[tauri::command]
+async fn discover_installations() -> Result<Vec<InstallCandidate>, String> {
+    tauri::async_runtime::spawn_blocking(game::discovery::discover)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Check an install for problems before launch: `game_root` when given
+/// (e.g. a discovered candidate), otherwise the current one. Works even when
+/// startup couldn't resolve paths, as long as a game folder is configured.
+#[tauri::command]
+fn validate_installation(
+    game_root: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<HealthReport, String> {
+    let config = state
+        .launcher_config
+        .read()
+        .map_err(|e| e.to_string())?
+        .effective()
+        .clone();
+    let explicit = |root: &str| {
+        let root = PathBuf::from(root.trim());
+        let appdata = GamePaths::resolve_appdata(&root);
+        (root, appdata)
+    };
+    let (root, appdata) = match (game_root, state.get_paths()) {
+        (Some(root), _) => explicit(&root),
+        (None, Ok(paths)) => (paths.game_root, paths.appdata),
+        (None, Err(e)) => match &config.game_root {
+            Some(root) => explicit(root),
+            None => return Err(e),
+        },
+    };
+    Ok(game::health::validate(&root, &appdata, &config))
+}
+
 #[tauri::command]
 fn exit_app(app: tauri::AppHandle) {
     logging::log("Exit requested by user.");
@@ -178,15 +741,26 @@ fn get_platform() -> String {
     }
 }
 
+/// What `launch_game` would run right now, without running it.
+#[tauri::command]
+fn get_launch_plan(state: tauri::State<'_, AppState>) -> Result<LaunchPlan, String> {
+    current_launch_plan(&state)
+}
+
 #[tauri::command]
 fn launch_game(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    game::launcher::spawn(&current_launch_plan(&state)?)
+}
+
+fn current_launch_plan(state: &AppState) -> Result<LaunchPlan, String> {
     let paths = state.get_paths()?;
     let config = state
         .launcher_config
         .read()
         .map_err(|e| e.to_string())?
+        .effective()
         .clone();
-    game::launcher::launch_game(&paths.game_root, &config)
+    Ok(game::launcher::plan(&paths.game_root, &config, &state.game_args))
 }
 
 #[tauri::command]
@@ -197,8 +771,476 @@ fn clear_shader_cache(state: tauri::State<'_, AppState>) -> Result<u64, String>
 
 #[tauri::command]
 fn reset_user_ltx(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    reset_user_ltx_recorded(&state, &state.get_paths()?)
+}
+
+/// Reset user.ltx to the defaults, journaling the change.
+fn reset_user_ltx_recorded(state: &AppState, paths: &GamePaths) -> Result<(), String> {
+    let before = UserLtx::load(&paths.appdata);
+    game::launcher::reset_user_ltx(&paths.appdata, &paths.game_root)?;
+    let after = UserLtx::load(&paths.appdata);
+    change_journal::record(
+        &paths.storage.config_dir,
+        &paths.game_root,
+        ChangeOrigin::Reset,
+        &before,
+        &after,
+    );
+    state.record_options_base(paths, &after)?;
+    defaults_sync::record_snapshot(&paths.appdata, &paths.game_root)
+}
+
+#[tauri::command]
+fn get_defaults_diff(state: tauri::State<'_, AppState>) -> Result<DefaultsDiff, String> {
+    let paths = state.get_paths()?;
+    let user = UserLtx::load(&paths.appdata);
+    let defaults = UserLtx::load_defaults(&paths.game_root);
+    let diff = defaults_diff::diff(&user, &defaults);
+    logging::log(format!(
+        "IPC: get_defaults_diff: {} changed, {} missing, {} extra",
+        diff.changed.len(),
+        diff.missing.len(),
+        diff.extra.len()
+    ));
+    Ok(diff)
+}
+
+#[tauri::command]
+fn reset_user_ltx_keys(
+    keys: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let paths = state.get_paths()?;
+    let defaults = UserLtx::load_defaults(&paths.game_root);
+    if defaults.commands().next().is_none() {
+        return Err("user_default.ltx not found or empty, nothing to reset to".to_string());
+    }
+
+    let mut user = UserLtx::load(&paths.appdata);
+    let before = user.clone();
+    let reset = defaults_diff::reset_keys(&mut user, &defaults, &keys);
+    if !reset.is_empty() {
+        user.save(&paths.appdata)?;
+        change_journal::record(
+            &paths.storage.config_dir,
+            &paths.game_root,
+            ChangeOrigin::Reset,
+            &before,
+            &user,
+        );
+        state.record_options_base(&paths, &user)?;
+    }
+
+    logging::log(format!("OWA: Reset {} user.ltx keys to defaults: {:?}", reset.len(), reset));
+    Ok(reset)
+}
+
+#[tauri::command]
+fn check_defaults_update(
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<DefaultsUpdate>, String> {
+    let paths = state.get_paths()?;
+    defaults_sync::check(&paths.appdata, &paths.game_root)
+}
+
+#[tauri::command]
+fn apply_defaults_update(
+    keys: Vec<String>,
+    force: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let paths = state.get_paths()?;
+    let before = UserLtx::load(&paths.appdata);
+    let force = force.unwrap_or(false);
+    let applied = defaults_sync::apply(&paths.appdata, &paths.game_root, &keys, force)?;
+    let after = UserLtx::load(&paths.appdata);
+    change_journal::record(
+        &paths.storage.config_dir,
+        &paths.game_root,
+        ChangeOrigin::Migration,
+        &before,
+        &after,
+    );
+    state.record_options_base(&paths, &after)?;
+    Ok(applied)
+}
+
+/// Make user.ltx read-only (locked) or writable again. A locked user.ltx
+/// keeps the game from resetting the player's tweaks on exit.
+#[tauri::command]
+fn set_settings_locked(locked: bool, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let paths = state.get_paths()?;
+    let user_path = UserLtx::file_path(&paths.appdata);
+    if !user_path.exists() {
+        return Err("user.ltx doesn't exist yet; save settings once before locking them".to_string());
+    }
+    fs_util::set_read_only(&user_path, locked)?;
+    logging::log(format!(
+        "OWA: user.ltx {}",
+        if locked { "locked (read-only)" } else { "unlocked" }
+    ));
+    Ok(())
+}
+
+/// Rebuild a damaged user.ltx from its valid lines plus user_default.ltx.
+/// The damaged file is kept as user.ltx.bak.
+#[tauri::command]
+fn repair_user_ltx(state: tauri::State<'_, AppState>) -> Result<RepairReport, String> {
+    let paths = state.get_paths()?;
+    let user_path = UserLtx::file_path(&paths.appdata);
+    let bytes = std::fs::read(&user_path)
+        .map_err(|e| format!("Failed to read {}: {}", user_path.display(), e))?;
+    let defaults = UserLtx::load_defaults(&paths.game_root);
+    if defaults.commands().next().is_none() {
+        return Err("user_default.ltx not found or empty, can't repair user.ltx".to_string());
+    }
+
+    let before = UserLtx::parse(&String::from_utf8_lossy(&bytes));
+    let (repaired, report) = integrity::repair(&bytes, &defaults);
+    repaired.save(&paths.appdata)?;
+    change_journal::record(
+        &paths.storage.config_dir,
+        &paths.game_root,
+        ChangeOrigin::Repair,
+        &before,
+        &repaired,
+    );
+
+    state.record_options_base(&paths, &repaired)?;
+
+    logging::log(format!(
+        "OWA: Repaired user.ltx: kept {}, dropped {}, filled {} from defaults",
+        report.kept,
+        report.dropped.len(),
+        report.filled.len()
+    ));
+    Ok(report)
+}
+
+/// List unknown and deprecated commands in user.ltx.
+#[tauri::command]
+fn scan_user_ltx(state: tauri::State<'_, AppState>) -> Result<CommandScan, String> {
     let paths = state.get_paths()?;
-    game::launcher::reset_user_ltx(&paths.appdata, &paths.game_root)
+    let user = UserLtx::load(&paths.appdata);
+    let defaults = UserLtx::load_defaults(&paths.game_root);
+    let scan = command_scan::scan(&user, &defaults);
+    logging::log(format!(
+        "IPC: scan_user_ltx: {} of {} commands flagged",
+        scan.findings.len(),
+        scan.checked
+    ));
+    Ok(scan)
+}
+
+/// Remove the given entries (keys from `scan_user_ltx`) from user.ltx.
+/// Returns the keys that were removed.
+#[tauri::command]
+fn remove_user_ltx_entries(
+    keys: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let paths = state.get_paths()?;
+    let user_path = UserLtx::file_path(&paths.appdata);
+    if !user_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut user = UserLtx::load(&paths.appdata);
+    let before = user.clone();
+    let removed: Vec<String> = keys.into_iter().filter(|key| user.remove_entry(key)).collect();
+    if removed.is_empty() {
+        return Ok(removed);
+    }
+
+    user.save(&paths.appdata)?;
+    change_journal::record(
+        &paths.storage.config_dir,
+        &paths.game_root,
+        ChangeOrigin::Ui,
+        &before,
+        &user,
+    );
+    state.record_options_base(&paths, &user)?;
+
+    logging::log(format!("OWA: Removed {} user.ltx entries: {:?}", removed.len(), removed));
+    Ok(removed)
+}
+
+/// Import user.ltx and axr_options.ltx from another Anomaly/OWA install.
+/// `path` may be the install root, its appdata folder or user.ltx itself.
+/// With `dry_run` only the preview is returned and nothing is written.
+#[tauri::command]
+fn import_settings(
+    path: String,
+    dry_run: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ImportPreview, String> {
+    let paths = state.get_paths()?;
+    let source = settings_import::locate(Path::new(&path))?;
+    if source.user_ltx == UserLtx::file_path(&paths.appdata) {
+        return Err("That is this installation's own user.ltx".to_string());
+    }
+
+    let (current, defaults) = load_user_or_defaults(&paths);
+    let current_axr = axr_options::load_values(&paths.game_root);
+    let preview = settings_import::plan(&source, &current, &defaults, &current_axr)?;
+
+    logging::log(format!(
+        "IPC: import_settings from {}: {} changes, {} axr_options changes, {} skipped",
+        source.user_ltx.display(),
+        preview.changes.len(),
+        preview.axr_changes.len(),
+        preview.skipped.len()
+    ));
+    if !dry_run.unwrap_or(false) {
+        apply_import(&state, &paths, &preview)?;
+    }
+    Ok(preview)
+}
+
+/// Export the settings in `groups` as a settings code, and to `path` as a
+/// settings file when given.
+#[tauri::command]
+fn export_settings(
+    groups: Vec<SchemaGroup>,
+    path: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<SettingsExport, String> {
+    if groups.is_empty() {
+        return Err("Choose at least one group of settings to export".to_string());
+    }
+    let paths = state.get_paths()?;
+    let (user, defaults) = load_user_or_defaults(&paths);
+    let axr = axr_options::load_values(&paths.game_root);
+    let shared = settings_share::collect(&user, &defaults, &axr, &groups);
+
+    if let Some(path) = &path {
+        settings_share::write_file(Path::new(path), &shared)?;
+    }
+    let code = settings_share::encode(&shared)?;
+
+    logging::log(format!(
+        "IPC: export_settings {:?}: {} commands, {} axr_options values, code {} chars",
+        groups,
+        shared.user_ltx.len(),
+        shared.axr_options.len(),
+        code.len()
+    ));
+    Ok(SettingsExport {
+        code,
+        path,
+        commands: shared.user_ltx.len(),
+        axr_options: shared.axr_options.len(),
+    })
+}
+
+/// Import shared settings from a settings code or a settings file path.
+/// Values are validated against the console schema; entries outside the
+/// groups the share declares are skipped. With `dry_run` only the preview
+/// is returned.
+#[tauri::command]
+fn import_shared_settings(
+    source: String,
+    dry_run: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ImportPreview, String> {
+    let paths = state.get_paths()?;
+    let (mut shared, label) = if settings_share::is_code(&source) {
+        (settings_share::decode(&source)?, "settings code".to_string())
+    } else {
+        (settings_share::read_file(Path::new(&source))?, source.clone())
+    };
+    let (current, defaults) = load_user_or_defaults(&paths);
+    let current_axr = axr_options::load_values(&paths.game_root);
+    let dropped = settings_share::restrict_to_groups(&mut shared, &current_axr);
+
+    let mut preview = settings_import::plan_values(
+        &shared.user_ltx,
+        &shared.axr_options,
+        &current,
+        &defaults,
+        &current_axr,
+    );
+    preview.source_user_ltx = label;
+    preview.skipped.extend(dropped);
+
+    logging::log(format!(
+        "IPC: import_shared_settings {:?}: {} changes, {} axr_options changes, {} skipped",
+        shared.groups,
+        preview.changes.len(),
+        preview.axr_changes.len(),
+        preview.skipped.len()
+    ));
+    if !dry_run.unwrap_or(false) {
+        apply_import(&state, &paths, &preview)?;
+    }
+    Ok(preview)
+}
+
+/// user.ltx, or user_default.ltx when there is no user.ltx yet, plus the defaults.
+fn load_user_or_defaults(paths: &GamePaths) -> (UserLtx, UserLtx) {
+    let defaults = UserLtx::load_defaults(&paths.game_root);
+    let user = if UserLtx::file_path(&paths.appdata).exists() {
+        UserLtx::load(&paths.appdata)
+    } else {
+        defaults.clone()
+    };
+    (user, defaults)
+}
+
+/// Write a previewed import to user.ltx and axr_options.ltx in one transaction.
+fn apply_import(state: &AppState, paths: &GamePaths, preview: &ImportPreview) -> Result<(), String> {
+    provision::ensure_provisioned(paths)?;
+    let user_path = UserLtx::file_path(&paths.appdata);
+    let mut user = UserLtx::load(&paths.appdata);
+    let before = user.clone();
+    settings_import::apply(preview, &mut user);
+
+    let mut tx = ConfigTransaction::new();
+    if !preview.changes.is_empty() {
+        tx.stage(&user_path, user.render(), true);
+    }
+    let axr_changes: Vec<(String, String)> = preview
+        .axr_changes
+        .iter()
+        .map(|change| (change.path.clone(), change.imported_value.clone()))
+        .collect();
+    axr_options::stage_changes(&mut tx, &paths.game_root, &axr_changes)?;
+    if !tx.is_empty() {
+        fs_util::with_unlocked(&user_path, || tx.commit())?;
+    }
+    change_journal::record(
+        &paths.storage.config_dir,
+        &paths.game_root,
+        ChangeOrigin::Import,
+        &before,
+        &user,
+    );
+
+    state.record_options_base(paths, &user)?;
+    Ok(())
+}
+
+/// Journal records, newest first, optionally limited to the last `limit`.
+#[tauri::command]
+fn get_change_history(
+    limit: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<JournalRecord>, String> {
+    let paths = state.get_paths()?;
+    let mut records = change_journal::load(&paths.storage.config_dir, &paths.game_root);
+    records.reverse();
+    if let Some(limit) = limit {
+        records.truncate(limit);
+    }
+    Ok(records)
+}
+
+/// Revert the most recent journaled change that hasn't been undone.
+#[tauri::command]
+fn undo(state: tauri::State<'_, AppState>) -> Result<UndoResult, String> {
+    replay_journal(&state, false)
+}
+
+/// Re-apply the most recently undone change.
+#[tauri::command]
+fn redo(state: tauri::State<'_, AppState>) -> Result<UndoResult, String> {
+    replay_journal(&state, true)
+}
+
+fn replay_journal(state: &AppState, forward: bool) -> Result<UndoResult, String> {
+    let paths = state.get_paths()?;
+    let records = change_journal::load(&paths.storage.config_dir, &paths.game_root);
+    let target = if forward {
+        change_journal::redo_target(&records)
+    } else {
+        change_journal::undo_target(&records)
+    };
+    let Some(target) = target else {
+        return Ok(UndoResult {
+            record: None,
+            skipped: Vec::new(),
+        });
+    };
+
+    let user_path = UserLtx::file_path(&paths.appdata);
+    if !user_path.exists() {
+        return Err(format!("{} does not exist, nothing to replay onto", user_path.display()));
+    }
+
+    let mut user = UserLtx::load(&paths.appdata);
+    let before = user.clone();
+    let skipped = change_journal::replay(&mut user, &target, forward);
+    let changes = change_journal::diff(&before, &user);
+    if !changes.is_empty() {
+        user.save(&paths.appdata)?;
+    }
+
+    // Recorded even when every key was skipped, so the undo/redo stacks advance.
+    let origin = if forward { ChangeOrigin::Redo } else { ChangeOrigin::Undo };
+    let record = change_journal::append(
+        &paths.storage.config_dir,
+        &paths.game_root,
+        origin,
+        Some(target.id),
+        changes,
+    )?;
+
+    state.record_options_base(&paths, &user)?;
+
+    logging::log(format!(
+        "OWA: {:?} of journal record {} ({} skipped: {:?})",
+        origin,
+        target.id,
+        skipped.len(),
+        skipped
+    ));
+    Ok(UndoResult { record, skipped })
+}
+
+// -- Background Tasks --
+
+/// Poll user.ltx and emit `user-ltx-changed` when something other than the
+/// launcher rewrites it, so the UI can reload. Polling keeps this free of
+/// platform watcher quirks (inotify under Proton, network drives).
+fn spawn_user_ltx_watcher(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut last_mtime = None;
+        loop {
+            std::thread::sleep(Duration::from_secs(2));
+
+            let state = app.state::<AppState>();
+            let Ok(paths) = state.get_paths() else {
+                continue;
+            };
+            let path = UserLtx::file_path(&paths.appdata);
+            let mtime = file_stamp::modified(&path);
+            if mtime == last_mtime {
+                continue;
+            }
+            let first_poll = last_mtime.is_none();
+            last_mtime = mtime;
+            if first_poll {
+                continue;
+            }
+
+            let external = match state.options_base.read() {
+                Ok(base) => base
+                    .as_ref()
+                    .is_some_and(|b| b.changed_on_disk(&path)),
+                Err(_) => false,
+            };
+            if external {
+                logging::log("user.ltx changed on disk, notifying UI");
+                let payload = UserLtxChangedEvent {
+                    path: path.display().to_string(),
+                };
+                if let Err(e) = app.emit("user-ltx-changed", payload) {
+                    logging::log(format!("ERROR: Failed to emit user-ltx-changed: {}", e));
+                }
+            }
+        }
+    });
 }
 
 // -- Tauri Entry Point --
@@ -208,10 +1250,25 @@ pub fn run() {
     // Initialize logging before anything else so we can capture all errors.
     // This is separate from AppState::initialize() so that even if path
     // resolution fails, we still have a log file to diagnose what happened.
-    if let Ok(exe_path) = std::env::current_exe() {
-        if let Some(dir) = exe_path.parent() {
-            logging::init(dir);
-        }
+    // Resolved first because the log itself may have to live in a per-user
+    // dir when the exe dir is read-only (AppImage).
+    let storage = storage::resolve();
+    logging::init(&storage.log_dir);
+    logging::log(format!(
+        "Storage: {} (config: {}, log: {})",
+        match storage.mode {
+            StorageMode::Portable => "portable",
+            StorageMode::User => "per-user",
+        },
+        storage.config_dir.display(),
+        storage.log_dir.display()
+    ));
+    if !storage.migrated.is_empty() {
+        logging::log(format!(
+            "Copied {:?} from the launcher dir into {}",
+            storage.migrated,
+            storage.config_dir.display()
+        ));
     }
 
     // Redirect panics to the log file — under Proton, stderr is invisible,
@@ -222,31 +1279,123 @@ pub fn run() {
         eprintln!("{}", msg);
     }));
 
-    let app_state = AppState::new();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli = match cli::parse(&args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            cli::attach_parent_console();
+            logging::log(format!("ERROR: {}", e));
+            eprintln!("error: {}\n", e);
+            cli::print_usage();
+            std::process::exit(cli::EXIT_USAGE);
+        }
+    };
+    if cli.is_headless() {
+        cli::attach_parent_console();
+    }
+    let game_root = match cli.game_root.as_deref().map(GamePaths::validate_game_root) {
+        Some(Ok(root)) => Some(root),
+        Some(Err(e)) if cli.is_headless() => {
+            logging::log(format!("ERROR: --game-root: {}", e));
+            eprintln!("error: --game-root: {}", e);
+            std::process::exit(cli::EXIT_INVALID_INSTALL);
+        }
+        Some(Err(e)) => {
+            logging::log(format!("WARNING: ignoring --game-root: {}", e));
+            None
+        }
+        None => None,
+    };
+
+    if !cli.game_args.is_empty() {
+        logging::log(format!("Forwarding to the game: {:?}", cli.game_args));
+    }
+    let app_state = AppState::new(storage, cli.game_args.clone());
 
     // Attempt initialization but don't fail — paths may not be available yet
     // (user might need to configure game_root through the UI)
-    if let Err(e) = app_state.initialize() {
+    // Everything after `--` is the game's, including any `--config`
+    let launcher_args = args.split(|a| a == "--").next().unwrap_or_default();
+    let init_error = app_state.initialize(launcher_args, game_root.as_deref()).err();
+    if let Some(e) = &init_error {
         logging::log(format!("ERROR: Initialization failed: {}", e));
         eprintln!("Old World Launcher: initialization failed: {}", e);
     }
 
+    if cli.is_headless() {
+        std::process::exit(cli::execute(&cli, &app_state, init_error.as_deref()));
+    }
+
+    let auto_launch = cli.play
+        || app_state
+            .launcher_config
+            .read()
+            .is_ok_and(|c| c.effective().auto_launch);
+    let settings_requested = cli.settings || cli::settings_key_held();
+    if auto_launch && settings_requested {
+        logging::log("Auto-launch skipped: settings requested.");
+    } else if auto_launch {
+        logging::log("Auto-launch...");
+        match cli::auto_launch(&app_state, init_error.as_deref()) {
+            Ok(()) => {
+                logging::log("Auto-launch: game started, not opening the window.");
+                return;
+            }
+            Err(e) => {
+                logging::log(format!("Auto-launch failed, opening the window: {}", e));
+                if let Ok(mut error) = app_state.auto_launch_error.write() {
+                    *error = Some(e);
+                }
+            }
+        }
+    }
+
     logging::log("Starting Tauri runtime...");
 
     let result = tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .manage(app_state)
+        .setup(|app| {
+            spawn_user_ltx_watcher(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_game_paths,
             get_options,
             save_options,
+            apply_settings,
+            provision_appdata,
             get_launcher_config,
+            get_config_recovery,
+            get_auto_launch_error,
             save_launcher_config,
+            set_game_root,
+            list_installations,
+            add_installation,
+            remove_installation,
+            select_installation,
+            discover_installations,
+            validate_installation,
             get_platform,
             exit_app,
+            get_launch_plan,
             launch_game,
             clear_shader_cache,
             reset_user_ltx,
+            get_defaults_diff,
+            reset_user_ltx_keys,
+            check_defaults_update,
+            apply_defaults_update,
+            set_settings_locked,
+            repair_user_ltx,
+            scan_user_ltx,
+            remove_user_ltx_entries,
+            import_settings,
+            export_settings,
+            import_shared_settings,
+            get_change_history,
+            undo,
+            redo,
         ])
         .run(tauri::generate_context!());
 
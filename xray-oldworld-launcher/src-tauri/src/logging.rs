@@ -7,12 +7,12 @@ static LOG_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
 
 /// Initialize the log file, truncating any previous contents.
 /// Call this as early as possible in the application lifecycle.
-pub fn init(launcher_dir: &Path) {
-    let log_path = launcher_dir.join("owl_launcher.log");
+pub fn init(log_dir: &Path) {
+    let log_path = log_dir.join("owl_launcher.log");
 
     if let Ok(mut f) = File::create(&log_path) {
         let _ = writeln!(f, "=== Old World Launcher ===");
-        let _ = writeln!(f, "Launcher dir: {}", launcher_dir.display());
+        let _ = writeln!(f, "Log dir: {}", log_dir.display());
         let _ = writeln!(f);
     }
 
@@ -0,0 +1,131 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::fs_util;
+
+/// Directory name used under the per-user config/state roots.
+const APP_DIR_NAME: &str = "owl-launcher";
+
+/// Launcher-owned files that live in the config dir and are carried over
+/// from a portable dir when the launcher switches to per-user storage.
+const MIGRATED_FILES: &[&str] = &["launcher_config.json", "settings_journal.jsonl"];
+
+/// Where the launcher keeps its own config and log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageMode {
+    /// Next to the executable (the exe dir is writable).
+    Portable,
+    /// Per-user dirs: XDG config/state on Linux, %APPDATA% on Windows. Used
+    /// when the exe dir is read-only, e.g. inside an AppImage.
+    User,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageLocation {
+    pub mode: StorageMode,
+    /// launcher_config.json and the settings journal.
+    pub config_dir: PathBuf,
+    /// owl_launcher.log.
+    pub log_dir: PathBuf,
+    /// Files copied from a portable dir into `config_dir` on this start.
+    pub migrated: Vec<String>,
+}
+
+/// Pick the storage location. Runs before logging is initialized, so it
+/// doesn't log; the caller logs the result once the log file exists.
+pub fn resolve() -> StorageLocation {
+    let exe_dir = env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf));
+
+    if let Some(dir) = &exe_dir
+        && fs_util::is_writable_dir(dir)
+    {
+        return StorageLocation {
+            mode: StorageMode::Portable,
+            config_dir: dir.clone(),
+            log_dir: dir.clone(),
+            migrated: Vec::new(),
+        };
+    }
+
+    let (config_dir, log_dir) = user_dirs();
+    let _ = fs::create_dir_all(&config_dir);
+    let _ = fs::create_dir_all(&log_dir);
+
+    let migrated = migration_sources(exe_dir)
+        .iter()
+        .flat_map(|dir| migrate_files(dir, &config_dir))
+        .collect();
+
+    StorageLocation {
+        mode: StorageMode::User,
+        config_dir,
+        log_dir,
+        migrated,
+    }
+}
+
+/// Per-user (config, log) directories for this platform.
+fn user_dirs() -> (PathBuf, PathBuf) {
+    let home = env::var_os("HOME").map(PathBuf::from);
+
+    if cfg!(windows) {
+        let appdata = env::var_os("APPDATA")
+            .map(PathBuf::from)
+            .unwrap_or_else(env::temp_dir)
+            .join(APP_DIR_NAME);
+        return (appdata.clone(), appdata);
+    }
+
+    let xdg = |var: &str, fallback: &[&str]| {
+        env::var_os(var)
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| {
+                home.as_ref()
+                    .map(|h| fallback.iter().fold(h.clone(), |p, s| p.join(s)))
+            })
+            .unwrap_or_else(env::temp_dir)
+            .join(APP_DIR_NAME)
+    };
+    (
+        xdg("XDG_CONFIG_HOME", &[".config"]),
+        xdg("XDG_STATE_HOME", &[".local", "state"]),
+    )
+}
+
+/// Directories that may hold a portable config to carry over, in order of
+/// preference. Inside an AppImage the exe dir is the read-only squashfs
+/// mount, which never has user files; a config from an older unpacked build
+/// sits next to the `.AppImage` file itself.
+fn migration_sources(exe_dir: Option<PathBuf>) -> Vec<PathBuf> {
+    let appimage_dir = env::var_os("APPIMAGE")
+        .map(PathBuf::from)
+        .and_then(|p| p.parent().map(Path::to_path_buf));
+    let mut sources: Vec<PathBuf> = appimage_dir.into_iter().collect();
+    if let Some(dir) = exe_dir
+        && !sources.contains(&dir)
+    {
+        sources.push(dir);
+    }
+    sources
+}
+
+/// Copy launcher files from a portable dir unless the user dir already has them.
+/// The originals stay put; the exe dir is read-only in this mode anyway.
+fn migrate_files(from: &Path, to: &Path) -> Vec<String> {
+    MIGRATED_FILES
+        .iter()
+        .filter(|name| from.join(name).is_file() && !to.join(name).exists())
+        .filter(|name| {
+            // Files from a read-only mount come out read-only; make the copy writable
+            fs::copy(from.join(name), to.join(name)).is_ok()
+                && fs_util::set_read_only(&to.join(name), false).is_ok()
+        })
+        .map(|name| name.to_string())
+        .collect()
+}
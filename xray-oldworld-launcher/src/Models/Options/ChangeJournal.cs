@@ -0,0 +1,55 @@
+using System.Text.Json.Serialization;
+
+namespace XrayOldworldLauncher.Models.Options;
+
+/// <summary>
+/// A saved settings change from the backend change journal.
+/// </summary>
+public class JournalRecord
+{
+    [JsonPropertyName("id")]
+    public ulong Id { get; set; }
+
+    /// Milliseconds since the Unix epoch
+    [JsonPropertyName("timestamp")]
+    public ulong Timestamp { get; set; }
+
+    /// ui, preset, reset, migration, import, undo or redo
+    [JsonPropertyName("origin")]
+    public string Origin { get; set; } = "";
+
+    /// For undo/redo: the record that was undone or redone
+    [JsonPropertyName("target")]
+    public ulong? Target { get; set; }
+
+    [JsonPropertyName("changes")]
+    public List<JournalChange> Changes { get; set; } = new();
+}
+
+public class JournalChange
+{
+    [JsonPropertyName("key")]
+    public string Key { get; set; } = "";
+
+    [JsonPropertyName("command")]
+    public string Command { get; set; } = "";
+
+    /// Null when the line was added
+    [JsonPropertyName("oldValue")]
+    public string? OldValue { get; set; }
+
+    /// Null when the line was removed
+    [JsonPropertyName("newValue")]
+    public string? NewValue { get; set; }
+}
+
+public class UndoResult
+{
+    /// Null when there was nothing to undo/redo
+    [JsonPropertyName("record")]
+    public JournalRecord? Record { get; set; }
+
+    /// Keys left alone because they changed since
+    [JsonPropertyName("skipped")]
+    public List<string> Skipped { get; set; } = new();
+}
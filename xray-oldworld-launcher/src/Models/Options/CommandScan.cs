@@ -0,0 +1,47 @@
+using System.Text.Json.Serialization;
+
+namespace XrayOldworldLauncher.Models.Options;
+
+/// <summary>
+/// Unknown and deprecated commands found in user.ltx.
+/// </summary>
+public class CommandScan
+{
+    [JsonPropertyName("findings")]
+    public List<ScanFinding> Findings { get; set; } = new();
+
+    [JsonPropertyName("checked")]
+    public int Checked { get; set; }
+
+    /// False when user_default.ltx is missing and only deprecated commands could be detected
+    [JsonPropertyName("defaultsAvailable")]
+    public bool DefaultsAvailable { get; set; }
+}
+
+public class ScanFinding
+{
+    /// Entry key to pass back for removal
+    [JsonPropertyName("key")]
+    public string Key { get; set; } = "";
+
+    [JsonPropertyName("command")]
+    public string Command { get; set; } = "";
+
+    [JsonPropertyName("value")]
+    public string Value { get; set; } = "";
+
+    [JsonPropertyName("line")]
+    public int Line { get; set; }
+
+    /// "unknown" or "deprecated"
+    [JsonPropertyName("kind")]
+    public string Kind { get; set; } = "";
+
+    /// Current form of a deprecated line
+    [JsonPropertyName("replacement")]
+    public string? Replacement { get; set; }
+
+    /// Closest known command name for likely typos
+    [JsonPropertyName("suggestion")]
+    public string? Suggestion { get; set; }
+}
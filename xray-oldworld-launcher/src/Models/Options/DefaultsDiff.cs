@@ -0,0 +1,40 @@
+using System.Text.Json.Serialization;
+
+namespace XrayOldworldLauncher.Models.Options;
+
+/// <summary>
+/// How user.ltx differs from user_default.ltx, from get_defaults_diff.
+/// </summary>
+public class DefaultsDiff
+{
+    /// In both files with different values
+    [JsonPropertyName("changed")]
+    public List<DiffEntry> Changed { get; set; } = new();
+
+    /// In user_default.ltx but not in user.ltx
+    [JsonPropertyName("missing")]
+    public List<DiffEntry> Missing { get; set; } = new();
+
+    /// In user.ltx but not in user_default.ltx
+    [JsonPropertyName("extra")]
+    public List<DiffEntry> Extra { get; set; } = new();
+}
+
+public class DiffEntry
+{
+    /// Command name, or "bind <action>" for bind lines
+    [JsonPropertyName("key")]
+    public string Key { get; set; } = "";
+
+    [JsonPropertyName("command")]
+    public string Command { get; set; } = "";
+
+    [JsonPropertyName("displayName")]
+    public string? DisplayName { get; set; }
+
+    [JsonPropertyName("userValue")]
+    public string? UserValue { get; set; }
+
+    [JsonPropertyName("defaultValue")]
+    public string? DefaultValue { get; set; }
+}
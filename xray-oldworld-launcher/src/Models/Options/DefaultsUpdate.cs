@@ -0,0 +1,46 @@
+using System.Text.Json.Serialization;
+
+namespace XrayOldworldLauncher.Models.Options;
+
+/// <summary>
+/// Defaults a mod update changed since user.ltx was last reconciled, from check_defaults_update.
+/// </summary>
+public class DefaultsUpdate
+{
+    /// New commands missing from user.ltx
+    [JsonPropertyName("added")]
+    public List<DefaultsUpdateEntry> Added { get; set; } = new();
+
+    /// Retuned defaults user.ltx still has at the old value
+    [JsonPropertyName("changed")]
+    public List<DefaultsUpdateEntry> Changed { get; set; } = new();
+
+    /// Retuned defaults the user changed; only written when forced
+    [JsonPropertyName("customized")]
+    public List<DefaultsUpdateEntry> Customized { get; set; } = new();
+
+    /// No earlier defaults to compare with, so only new commands are listed
+    [JsonPropertyName("firstReconcile")]
+    public bool FirstReconcile { get; set; }
+}
+
+public class DefaultsUpdateEntry
+{
+    [JsonPropertyName("key")]
+    public string Key { get; set; } = "";
+
+    [JsonPropertyName("command")]
+    public string Command { get; set; } = "";
+
+    [JsonPropertyName("displayName")]
+    public string? DisplayName { get; set; }
+
+    [JsonPropertyName("userValue")]
+    public string? UserValue { get; set; }
+
+    [JsonPropertyName("oldDefault")]
+    public string? OldDefault { get; set; }
+
+    [JsonPropertyName("newDefault")]
+    public string NewDefault { get; set; } = "";
+}
@@ -15,4 +15,23 @@ public class GamePaths
 
     [JsonPropertyName("launcherDir")]
     public string LauncherDir { get; set; } = "";
+
+    [JsonPropertyName("storage")]
+    public StorageLocation Storage { get; set; } = new();
+}
+
+public class StorageLocation
+{
+    /// <summary>"portable" (next to the exe) or "user" (per-user config dirs).</summary>
+    [JsonPropertyName("mode")]
+    public string Mode { get; set; } = "portable";
+
+    [JsonPropertyName("configDir")]
+    public string ConfigDir { get; set; } = "";
+
+    [JsonPropertyName("logDir")]
+    public string LogDir { get; set; } = "";
+
+    [JsonPropertyName("migrated")]
+    public List<string> Migrated { get; set; } = new();
 }
@@ -0,0 +1,42 @@
+using System.Text.Json.Serialization;
+
+namespace XrayOldworldLauncher.Models.Options;
+
+/// <summary>
+/// Result of validate_installation: every check, passed or not.
+/// </summary>
+public class HealthReport
+{
+    [JsonPropertyName("gameRoot")]
+    public string GameRoot { get; set; } = "";
+
+    [JsonPropertyName("checks")]
+    public List<HealthCheck> Checks { get; set; } = new();
+
+    /// No failed check is an error
+    [JsonPropertyName("launchable")]
+    public bool Launchable { get; set; }
+
+    public bool AllPassed => Checks.All(c => c.Passed);
+}
+
+public class HealthCheck
+{
+    /// "gameRoot", "standardExe", "avxExe", "gamedata", "dbArchives", "fsgame",
+    /// "appdataWritable", "userDefaults" or "diskSpace"
+    [JsonPropertyName("id")]
+    public string Id { get; set; } = "";
+
+    [JsonPropertyName("passed")]
+    public bool Passed { get; set; }
+
+    /// "info", "warning" or "error"; how bad it is when the check failed
+    [JsonPropertyName("severity")]
+    public string Severity { get; set; } = "info";
+
+    [JsonPropertyName("detail")]
+    public string Detail { get; set; } = "";
+
+    [JsonPropertyName("fix")]
+    public string? Fix { get; set; }
+}
@@ -0,0 +1,74 @@
+using System.Text.Json.Serialization;
+
+namespace XrayOldworldLauncher.Models.Options;
+
+/// <summary>
+/// What importing settings from another install would change.
+/// </summary>
+public class ImportPreview
+{
+    [JsonPropertyName("sourceUserLtx")]
+    public string SourceUserLtx { get; set; } = "";
+
+    [JsonPropertyName("sourceAxrOptions")]
+    public string? SourceAxrOptions { get; set; }
+
+    [JsonPropertyName("changes")]
+    public List<ImportChange> Changes { get; set; } = new();
+
+    [JsonPropertyName("axrChanges")]
+    public List<ImportAxrChange> AxrChanges { get; set; } = new();
+
+    [JsonPropertyName("skipped")]
+    public List<SkippedCommand> Skipped { get; set; } = new();
+
+    /// Source entries that already match this install
+    [JsonPropertyName("unchanged")]
+    public int Unchanged { get; set; }
+}
+
+public class ImportChange
+{
+    [JsonPropertyName("key")]
+    public string Key { get; set; } = "";
+
+    [JsonPropertyName("command")]
+    public string Command { get; set; } = "";
+
+    [JsonPropertyName("displayName")]
+    public string? DisplayName { get; set; }
+
+    [JsonPropertyName("currentValue")]
+    public string? CurrentValue { get; set; }
+
+    [JsonPropertyName("importedValue")]
+    public string ImportedValue { get; set; } = "";
+
+    /// Old command name when it was renamed
+    [JsonPropertyName("renamedFrom")]
+    public string? RenamedFrom { get; set; }
+}
+
+public class ImportAxrChange
+{
+    [JsonPropertyName("path")]
+    public string Path { get; set; } = "";
+
+    [JsonPropertyName("currentValue")]
+    public string? CurrentValue { get; set; }
+
+    [JsonPropertyName("importedValue")]
+    public string ImportedValue { get; set; } = "";
+}
+
+public class SkippedCommand
+{
+    [JsonPropertyName("command")]
+    public string Command { get; set; } = "";
+
+    [JsonPropertyName("value")]
+    public string Value { get; set; } = "";
+
+    [JsonPropertyName("reason")]
+    public string Reason { get; set; } = "";
+}
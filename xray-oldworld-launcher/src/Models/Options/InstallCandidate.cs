@@ -0,0 +1,36 @@
+using System.Text.Json.Serialization;
+
+namespace XrayOldworldLauncher.Models.Options;
+
+/// <summary>
+/// A folder that looks like a game install, found by discover_installations.
+/// </summary>
+public class InstallCandidate
+{
+    [JsonPropertyName("gameRoot")]
+    public string GameRoot { get; set; } = "";
+
+    /// "launcher", "steam", "proton", "lutris", "heroic", "wine", "home" or "drive"
+    [JsonPropertyName("source")]
+    public string Source { get; set; } = "";
+
+    /// "oldWorld" or "anomaly" (no Old World executables)
+    [JsonPropertyName("kind")]
+    public string Kind { get; set; } = "";
+
+    [JsonPropertyName("version")]
+    public string? Version { get; set; }
+
+    [JsonPropertyName("executables")]
+    public List<string> Executables { get; set; } = new();
+
+    [JsonPropertyName("hasFsgame")]
+    public bool HasFsgame { get; set; }
+
+    [JsonPropertyName("hasUserLtx")]
+    public bool HasUserLtx { get; set; }
+
+    /// Higher is a better match; the list comes sorted by it
+    [JsonPropertyName("score")]
+    public int Score { get; set; }
+}
@@ -0,0 +1,35 @@
+using System.Text.Json.Serialization;
+
+namespace XrayOldworldLauncher.Models.Options;
+
+/// <summary>
+/// A named game install with its own launch settings. Unset (null) settings
+/// fall back to the defaults of the mod in that install.
+/// </summary>
+public class Installation
+{
+    [JsonPropertyName("name")]
+    public string Name { get; set; } = "";
+
+    [JsonPropertyName("gameRoot")]
+    public string GameRoot { get; set; } = "";
+
+    [JsonPropertyName("useAvx")]
+    public bool? UseAvx { get; set; }
+
+    [JsonPropertyName("customArgs")]
+    public string? CustomArgs { get; set; }
+
+    [JsonPropertyName("linuxCustomCommand")]
+    public string? LinuxCustomCommand { get; set; }
+}
+
+public class InstallationList
+{
+    [JsonPropertyName("installations")]
+    public List<Installation> Installations { get; set; } = new();
+
+    /// Name of the selected installation, if any
+    [JsonPropertyName("active")]
+    public string? Active { get; set; }
+}
@@ -0,0 +1,59 @@
+using System.Text.Json.Serialization;
+
+namespace XrayOldworldLauncher.Models.Options;
+
+/// <summary>
+/// Problems found in a damaged user.ltx.
+/// </summary>
+public class IntegrityReport
+{
+    [JsonPropertyName("issues")]
+    public List<IntegrityIssue> Issues { get; set; } = new();
+
+    [JsonPropertyName("commandCount")]
+    public int CommandCount { get; set; }
+
+    [JsonPropertyName("defaultCount")]
+    public int DefaultCount { get; set; }
+}
+
+public class IntegrityIssue
+{
+    /// binaryContent, truncatedLastLine, garbledLine, missingValue, presetMissing or tooFewCommands
+    [JsonPropertyName("kind")]
+    public string Kind { get; set; } = "";
+
+    [JsonPropertyName("line")]
+    public int? Line { get; set; }
+
+    [JsonPropertyName("detail")]
+    public string Detail { get; set; } = "";
+}
+
+/// <summary>
+/// What repairing user.ltx did.
+/// </summary>
+public class RepairReport
+{
+    [JsonPropertyName("kept")]
+    public int Kept { get; set; }
+
+    [JsonPropertyName("dropped")]
+    public List<DroppedLine> Dropped { get; set; } = new();
+
+    /// Entry keys filled in from user_default.ltx
+    [JsonPropertyName("filled")]
+    public List<string> Filled { get; set; } = new();
+}
+
+public class DroppedLine
+{
+    [JsonPropertyName("line")]
+    public int Line { get; set; }
+
+    [JsonPropertyName("text")]
+    public string Text { get; set; } = "";
+
+    [JsonPropertyName("reason")]
+    public string Reason { get; set; } = "";
+}
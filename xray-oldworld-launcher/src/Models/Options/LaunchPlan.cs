@@ -0,0 +1,53 @@
+using System.Text.Json.Serialization;
+
+namespace XrayOldworldLauncher.Models.Options;
+
+/// <summary>
+/// What launch_game would run, from get_launch_plan.
+/// </summary>
+public class LaunchPlan
+{
+    /// "direct" (the exe) or "shell" (the Linux custom command via sh -c)
+    [JsonPropertyName("runner")]
+    public string Runner { get; set; } = "direct";
+
+    [JsonPropertyName("program")]
+    public string Program { get; set; } = "";
+
+    [JsonPropertyName("argv")]
+    public List<string> Argv { get; set; } = new();
+
+    [JsonPropertyName("env")]
+    public Dictionary<string, string> Env { get; set; } = new();
+
+    [JsonPropertyName("cwd")]
+    public string Cwd { get; set; } = "";
+
+    [JsonPropertyName("exe")]
+    public string Exe { get; set; } = "";
+
+    /// Values substituted for {exe} and {args} in the custom command
+    [JsonPropertyName("placeholders")]
+    public Dictionary<string, string> Placeholders { get; set; } = new();
+
+    [JsonPropertyName("warnings")]
+    public List<LaunchWarning> Warnings { get; set; } = new();
+
+    /// The whole command, quoted, for support requests
+    [JsonPropertyName("commandLine")]
+    public string CommandLine { get; set; } = "";
+}
+
+public class LaunchWarning
+{
+    /// "exeMissing", "noLinuxCommand", "noExePlaceholder" or "argsNotPassed"
+    [JsonPropertyName("id")]
+    public string Id { get; set; } = "";
+
+    [JsonPropertyName("message")]
+    public string Message { get; set; } = "";
+
+    /// Launching is refused until this is fixed
+    [JsonPropertyName("blocking")]
+    public bool Blocking { get; set; }
+}
@@ -4,6 +4,9 @@ namespace XrayOldworldLauncher.Models.Options;
 
 public class LauncherConfig
 {
+    [JsonPropertyName("schemaVersion")]
+    public int SchemaVersion { get; set; } = 1;
+
     [JsonPropertyName("useAvx")]
     public bool UseAvx { get; set; }
 
@@ -25,9 +28,60 @@ public class LauncherConfig
     [JsonPropertyName("linuxCustomCommand")]
     public string? LinuxCustomCommand { get; set; }
 
+    /// Start the game on launcher startup; the window only opens on error
+    [JsonPropertyName("autoLaunch")]
+    public bool AutoLaunch { get; set; }
+
     [JsonPropertyName("dynamicLightingPreset")]
     public string DynamicLightingPreset { get; set; } = "medium";
 
     [JsonPropertyName("staticLightingPreset")]
     public string StaticLightingPreset { get; set; } = "medium";
+
+    /// Where each effective value came from; ignored by the backend on save
+    [JsonPropertyName("layers")]
+    public Dictionary<string, FieldSource> Layers { get; set; } = new();
+
+    public bool IsLocked(string field) => Layers.TryGetValue(field, out var source) && source.Locked;
+}
+
+/// <summary>
+/// Source of a launcher setting: "builtin", "mod" (launcher_defaults.json),
+/// "user" or "override" (env/command line).
+/// </summary>
+public class FieldSource
+{
+    [JsonPropertyName("layer")]
+    public string Layer { get; set; } = "builtin";
+
+    /// Locked by the mod; the UI shouldn't offer to change it
+    [JsonPropertyName("locked")]
+    public bool Locked { get; set; }
+}
+
+/// <summary>
+/// Problems found loading launcher_config.json at startup.
+/// </summary>
+public class ConfigRecovery
+{
+    [JsonPropertyName("problems")]
+    public List<ConfigProblem> Problems { get; set; } = new();
+
+    /// Copy of the original file
+    [JsonPropertyName("corruptCopy")]
+    public string? CorruptCopy { get; set; }
+
+    /// Schema of a config written by a newer launcher; shown once, not a problem
+    [JsonPropertyName("newerSchema")]
+    public uint? NewerSchema { get; set; }
+}
+
+public class ConfigProblem
+{
+    /// Field reset to its default; null for whole-file problems
+    [JsonPropertyName("field")]
+    public string? Field { get; set; }
+
+    [JsonPropertyName("message")]
+    public string Message { get; set; } = "";
 }
@@ -9,4 +9,68 @@ public class OptionsState
 
     [JsonPropertyName("userLtx")]
     public Dictionary<string, string> UserLtx { get; set; } = new();
+
+    /// Ordered user.ltx entries with source, default value and validity
+    [JsonPropertyName("entries")]
+    public List<OptionEntry> Entries { get; set; } = new();
+
+    [JsonPropertyName("userLtxPath")]
+    public string UserLtxPath { get; set; } = "";
+
+    /// Milliseconds since the Unix epoch, null if user.ltx doesn't exist
+    [JsonPropertyName("userLtxModified")]
+    public long? UserLtxModified { get; set; }
+
+    /// No user.ltx yet; values come from user_default.ltx
+    [JsonPropertyName("usingDefaults")]
+    public bool UsingDefaults { get; set; }
+
+    /// Set when user.ltx looks damaged and should be repaired
+    [JsonPropertyName("integrity")]
+    public IntegrityReport? Integrity { get; set; }
+
+    /// user.ltx is read-only so the game can't overwrite it
+    [JsonPropertyName("settingsLocked")]
+    public bool SettingsLocked { get; set; }
+}
+
+public class OptionEntry
+{
+    [JsonPropertyName("key")]
+    public string Key { get; set; } = "";
+
+    [JsonPropertyName("command")]
+    public string Command { get; set; } = "";
+
+    [JsonPropertyName("displayName")]
+    public string? DisplayName { get; set; }
+
+    [JsonPropertyName("value")]
+    public string? Value { get; set; }
+
+    [JsonPropertyName("defaultValue")]
+    public string? DefaultValue { get; set; }
+
+    /// "user", "default" or "missing"
+    [JsonPropertyName("source")]
+    public string Source { get; set; } = "";
+
+    /// Value differs from user_default.ltx
+    [JsonPropertyName("modified")]
+    public bool Modified { get; set; }
+
+    [JsonPropertyName("valid")]
+    public bool Valid { get; set; } = true;
+
+    [JsonPropertyName("validationError")]
+    public string? ValidationError { get; set; }
+}
+
+/// <summary>
+/// Payload of the `user-ltx-changed` event: user.ltx was rewritten outside the launcher.
+/// </summary>
+public class UserLtxChangedEvent
+{
+    [JsonPropertyName("path")]
+    public string Path { get; set; } = "";
 }
@@ -0,0 +1,81 @@
+using System.Text.Json.Serialization;
+
+namespace XrayOldworldLauncher.Models.Options;
+
+public class SaveResult
+{
+    /// False when conflicts prevented the write.
+    [JsonPropertyName("saved")]
+    public bool Saved { get; set; }
+
+    /// user.ltx changed on disk since it was loaded and the changes were merged onto it.
+    [JsonPropertyName("mergedExternalChanges")]
+    public bool MergedExternalChanges { get; set; }
+
+    [JsonPropertyName("conflicts")]
+    public List<SaveConflict> Conflicts { get; set; } = new();
+
+    /// Only set for dry runs
+    [JsonPropertyName("preview")]
+    public SavePreview? Preview { get; set; }
+}
+
+public class SavePreview
+{
+    [JsonPropertyName("changes")]
+    public List<PreviewChange> Changes { get; set; } = new();
+
+    [JsonPropertyName("addedKeys")]
+    public List<string> AddedKeys { get; set; } = new();
+
+    [JsonPropertyName("removedLines")]
+    public List<string> RemovedLines { get; set; } = new();
+
+    [JsonPropertyName("clearsShaderCache")]
+    public bool ClearsShaderCache { get; set; }
+
+    [JsonPropertyName("requiresRestart")]
+    public bool RequiresRestart { get; set; }
+
+    [JsonPropertyName("fileText")]
+    public string FileText { get; set; } = "";
+}
+
+public class PreviewChange
+{
+    [JsonPropertyName("command")]
+    public string Command { get; set; } = "";
+
+    [JsonPropertyName("displayName")]
+    public string? DisplayName { get; set; }
+
+    [JsonPropertyName("before")]
+    public string? Before { get; set; }
+
+    [JsonPropertyName("after")]
+    public string After { get; set; } = "";
+
+    [JsonPropertyName("clearsShaderCache")]
+    public bool ClearsShaderCache { get; set; }
+
+    [JsonPropertyName("requiresRestart")]
+    public bool RequiresRestart { get; set; }
+}
+
+public class SaveConflict
+{
+    [JsonPropertyName("command")]
+    public string Command { get; set; } = "";
+
+    [JsonPropertyName("displayName")]
+    public string? DisplayName { get; set; }
+
+    [JsonPropertyName("baseValue")]
+    public string? BaseValue { get; set; }
+
+    [JsonPropertyName("diskValue")]
+    public string? DiskValue { get; set; }
+
+    [JsonPropertyName("pendingValue")]
+    public string PendingValue { get; set; } = "";
+}
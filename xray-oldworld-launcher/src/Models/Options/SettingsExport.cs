@@ -0,0 +1,23 @@
+using System.Text.Json.Serialization;
+
+namespace XrayOldworldLauncher.Models.Options;
+
+/// <summary>
+/// Result of exporting a subset of settings for sharing.
+/// </summary>
+public class SettingsExport
+{
+    /// Compact copy-pasteable code (OWL1:...)
+    [JsonPropertyName("code")]
+    public string Code { get; set; } = "";
+
+    /// Settings file written, if one was requested
+    [JsonPropertyName("path")]
+    public string? Path { get; set; }
+
+    [JsonPropertyName("commands")]
+    public int Commands { get; set; }
+
+    [JsonPropertyName("axrOptions")]
+    public int AxrOptions { get; set; }
+}
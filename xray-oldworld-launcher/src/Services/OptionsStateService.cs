@@ -19,12 +19,28 @@ public class OptionsStateService
     /// Current values from user.ltx (console commands)
     public Dictionary<string, string> CmdValues { get; private set; } = new();
 
+    /// Per-command detail from the backend, keyed by console command
+    public Dictionary<string, OptionEntry> CmdEntries { get; private set; } = new();
+
 
     /// Pending unsaved changes keyed by full option path
     public Dictionary<string, OptionChange> PendingChanges { get; } = new();
 
     public bool HasPendingChanges => PendingChanges.Count > 0;
 
+    /// Change journal origin for the next save: "preset" once a preset was applied.
+    private string _pendingOrigin = "ui";
+
+    /// Conflicts from the last save: commands the game changed in user.ltx
+    /// while the launcher had pending edits for them. Empty after a successful save.
+    public List<SaveConflict> SaveConflicts { get; private set; } = new();
+
+    /// Problems found in user.ltx on the last load; null when it looks fine.
+    public IntegrityReport? Integrity { get; private set; }
+
+    /// user.ltx is read-only; launcher saves unlock and re-lock it.
+    public bool SettingsLocked { get; private set; }
+
     public event Action? OnStateChanged;
 
     public OptionsStateService(Tauri tauri)
@@ -68,6 +84,12 @@ public class OptionsStateService
             if (state != null)
             {
                 CmdValues = state.UserLtx;
+                CmdEntries = state.Entries
+                    .Where(e => e.Key == e.Command)
+                    .GroupBy(e => e.Command)
+                    .ToDictionary(g => g.Key, g => g.Last());
+                Integrity = state.Integrity;
+                SettingsLocked = state.SettingsLocked;
             }
         }
         catch (Exception ex)
@@ -76,6 +98,42 @@ public class OptionsStateService
         }
     }
 
+    /// Raised after user.ltx was rewritten outside the launcher (usually the
+    /// game on exit). The argument is true when the values were reloaded,
+    /// false when pending changes kept them as they were.
+    public event Action<bool>? OnUserLtxChangedOnDisk;
+
+    /// Raised after the backend switched to another game folder and the
+    /// option values of the new install were loaded.
+    public event Action? OnGameRootChanged;
+
+    /// Follow backend events. On `user-ltx-changed` without pending changes the
+    /// values are reloaded; otherwise they stay put and the next save merges
+    /// the pending changes with the file or reports conflicts. On
+    /// `game-root-changed` pending changes belonged to the previous install,
+    /// so they're dropped before reloading.
+    public async Task ListenForBackendEventsAsync()
+    {
+        await _tauri.Event.Listen<UserLtxChangedEvent>("user-ltx-changed", async _ =>
+        {
+            var reload = !HasPendingChanges;
+            if (reload)
+            {
+                await RefreshCurrentValuesAsync();
+                OnStateChanged?.Invoke();
+            }
+            OnUserLtxChangedOnDisk?.Invoke(reload);
+        });
+        await _tauri.Event.Listen<GamePaths>("game-root-changed", async _ =>
+        {
+            PendingChanges.Clear();
+            _pendingOrigin = "ui";
+            await RefreshCurrentValuesAsync();
+            OnStateChanged?.Invoke();
+            OnGameRootChanged?.Invoke();
+        });
+    }
+
     /// Get the current display value for an option.
     /// Priority: PendingChanges → CmdValues/AxrValues → Defaults → hardcoded default.
     public string GetCurrentValue(OptionDefinition opt, string pageId, string groupId)
@@ -97,6 +155,12 @@ public class OptionsStateService
         return opt.DefaultValue;
     }
 
+    /// Whether the saved value of an option differs from the OWA default (user_default.ltx).
+    public bool IsModifiedFromDefault(OptionDefinition opt)
+        => opt.ConsoleCommand != null
+           && CmdEntries.TryGetValue(opt.ConsoleCommand, out var entry)
+           && entry.Modified;
+
     /// Record a pending change for an option.
     public void SetPendingChange(OptionDefinition opt, string pageId, string groupId, string value)
     {
@@ -117,7 +181,9 @@ public class OptionsStateService
     /// Save all pending changes to the backend.
     /// Also writes default values for any options missing from user.ltx,
     /// ensuring deleted/missing config entries are restored.
-    public async Task SaveAllAsync()
+    /// When user.ltx changed on disk with overlapping edits, the save is refused
+    /// and SaveConflicts is populated; pass force to overwrite them.
+    public async Task SaveAllAsync(bool force = false)
     {
         if (!HasPendingChanges) return;
 
@@ -141,13 +207,33 @@ public class OptionsStateService
         // User's pending changes come after so they override any matching defaults
         changes.AddRange(PendingChanges.Values);
 
-        await _tauri.Core.Invoke("save_options", new { changes });
+        var result = await _tauri.Core.Invoke<SaveResult>("save_options",
+            new { changes, force, origin = _pendingOrigin });
+        if (result is { Saved: false })
+        {
+            // Keep pending changes so the user can review and retry
+            SaveConflicts = result.Conflicts;
+            OnStateChanged?.Invoke();
+            return;
+        }
 
+        SaveConflicts = new();
         PendingChanges.Clear();
+        _pendingOrigin = "ui";
         await RefreshCurrentValuesAsync();
         OnStateChanged?.Invoke();
     }
 
+    /// Ask the backend what saving the pending changes would do, without writing anything.
+    public async Task<SavePreview?> PreviewSaveAsync()
+    {
+        if (!HasPendingChanges) return null;
+
+        var result = await _tauri.Core.Invoke<SaveResult>("save_options",
+            new { changes = PendingChanges.Values.ToList(), dryRun = true });
+        return result?.Preview;
+    }
+
     /// Reset all options on a page to their default values.
     public async Task ResetToDefaultsAsync(OptionPage page)
     {
@@ -202,6 +288,7 @@ public class OptionsStateService
     public void DiscardPendingChanges()
     {
         PendingChanges.Clear();
+        _pendingOrigin = "ui";
         OnStateChanged?.Invoke();
     }
 
@@ -291,5 +378,182 @@ public class OptionsStateService
                 SetPendingChange(match.Opt, match.PageId, match.GroupId, value);
             }
         }
+        _pendingOrigin = "preset";
+    }
+
+    /// Revert the last saved change. Returns false if there was nothing to undo.
+    public Task<bool> UndoAsync() => ReplayJournalAsync("undo");
+
+    /// Re-apply the last undone change. Returns false if there was nothing to redo.
+    public Task<bool> RedoAsync() => ReplayJournalAsync("redo");
+
+    private async Task<bool> ReplayJournalAsync(string command)
+    {
+        var result = await _tauri.Core.Invoke<UndoResult>(command);
+        if (result?.Record == null) return false;
+
+        await RefreshCurrentValuesAsync();
+        OnStateChanged?.Invoke();
+        return true;
+    }
+
+    /// Import settings from another Anomaly/OWA install. Call with dryRun first
+    /// to show the preview, then again without it to apply.
+    public async Task<ImportPreview?> ImportSettingsAsync(string path, bool dryRun)
+    {
+        var preview = await _tauri.Core.Invoke<ImportPreview>("import_settings", new { path, dryRun });
+        if (!dryRun)
+        {
+            await RefreshCurrentValuesAsync();
+            OnStateChanged?.Invoke();
+        }
+        return preview;
+    }
+
+    /// Export the given option pages ("video", "sound", "control") as a shareable
+    /// code, and to a settings file when a path is given.
+    public async Task<SettingsExport?> ExportSettingsAsync(IEnumerable<string> groups, string? path = null)
+    {
+        return await _tauri.Core.Invoke<SettingsExport>("export_settings",
+            new { groups = groups.ToList(), path });
+    }
+
+    /// Import a shared settings code or settings file. Call with dryRun first
+    /// to show the preview, then again without it to apply.
+    public async Task<ImportPreview?> ImportSharedSettingsAsync(string source, bool dryRun)
+    {
+        var preview = await _tauri.Core.Invoke<ImportPreview>("import_shared_settings",
+            new { source, dryRun });
+        if (!dryRun)
+        {
+            await RefreshCurrentValuesAsync();
+            OnStateChanged?.Invoke();
+        }
+        return preview;
+    }
+
+    /// Lock (read-only) or unlock user.ltx so the game can't reset it.
+    public async Task SetSettingsLockedAsync(bool locked)
+    {
+        await _tauri.Core.Invoke("set_settings_locked", new { locked });
+        SettingsLocked = locked;
+        OnStateChanged?.Invoke();
+    }
+
+    /// Rebuild a damaged user.ltx from its valid lines and the defaults.
+    public async Task<RepairReport?> RepairUserLtxAsync()
+    {
+        var report = await _tauri.Core.Invoke<RepairReport>("repair_user_ltx");
+        await RefreshCurrentValuesAsync();
+        OnStateChanged?.Invoke();
+        return report;
+    }
+
+    /// List unknown and deprecated commands in user.ltx.
+    public async Task<CommandScan?> ScanUserLtxAsync()
+    {
+        return await _tauri.Core.Invoke<CommandScan>("scan_user_ltx");
+    }
+
+    /// Remove the selected scan findings from user.ltx. Returns the keys removed.
+    public async Task<List<string>> RemoveUserLtxEntriesAsync(IEnumerable<string> keys)
+    {
+        var removed = await _tauri.Core.Invoke<List<string>>("remove_user_ltx_entries",
+            new { keys = keys.ToList() }) ?? new();
+        if (removed.Count > 0)
+        {
+            await RefreshCurrentValuesAsync();
+            OnStateChanged?.Invoke();
+        }
+        return removed;
+    }
+
+    /// How user.ltx differs from user_default.ltx.
+    public async Task<DefaultsDiff?> GetDefaultsDiffAsync()
+    {
+        return await _tauri.Core.Invoke<DefaultsDiff>("get_defaults_diff");
+    }
+
+    /// Reset the given entries (keys from GetDefaultsDiffAsync) to user_default.ltx.
+    /// Returns the keys that changed.
+    public async Task<List<string>> ResetUserLtxKeysAsync(IEnumerable<string> keys)
+    {
+        var reset = await _tauri.Core.Invoke<List<string>>("reset_user_ltx_keys",
+            new { keys = keys.ToList() }) ?? new();
+        if (reset.Count > 0)
+        {
+            await RefreshCurrentValuesAsync();
+            OnStateChanged?.Invoke();
+        }
+        return reset;
+    }
+
+    /// Defaults the mod changed since user.ltx was last reconciled, or null
+    /// when there is nothing to offer.
+    public async Task<DefaultsUpdate?> CheckDefaultsUpdateAsync()
+    {
+        return await _tauri.Core.Invoke<DefaultsUpdate?>("check_defaults_update");
+    }
+
+    /// Merge the chosen new defaults into user.ltx. An empty list dismisses
+    /// the offer; keys the user customized are only written with force.
+    public async Task<List<string>> ApplyDefaultsUpdateAsync(IEnumerable<string> keys, bool force)
+    {
+        var applied = await _tauri.Core.Invoke<List<string>>("apply_defaults_update",
+            new { keys = keys.ToList(), force }) ?? new();
+        if (applied.Count > 0)
+        {
+            await RefreshCurrentValuesAsync();
+            OnStateChanged?.Invoke();
+        }
+        return applied;
+    }
+
+    /// Look for game installs in Steam libraries, Wine prefixes and other
+    /// common places, best match first.
+    public async Task<List<InstallCandidate>> DiscoverInstallationsAsync()
+    {
+        return await _tauri.Core.Invoke<List<InstallCandidate>>("discover_installations")
+            ?? new();
+    }
+
+    /// Point the launcher at another game folder. The values are reloaded
+    /// when the backend reports `game-root-changed`.
+    public async Task<GamePaths?> SetGameRootAsync(string gameRoot)
+    {
+        return await _tauri.Core.Invoke<GamePaths>("set_game_root", new { gameRoot });
+    }
+
+    /// Registered installations and which one is selected.
+    public async Task<InstallationList> ListInstallationsAsync()
+    {
+        return await _tauri.Core.Invoke<InstallationList>("list_installations") ?? new();
+    }
+
+    /// Add an installation, or replace the one with the same name.
+    public async Task<InstallationList> AddInstallationAsync(Installation installation)
+    {
+        return await _tauri.Core.Invoke<InstallationList>("add_installation", new { installation })
+            ?? new();
+    }
+
+    public async Task<InstallationList> RemoveInstallationAsync(string name)
+    {
+        return await _tauri.Core.Invoke<InstallationList>("remove_installation", new { name })
+            ?? new();
+    }
+
+    /// Switch to a registered installation. Like SetGameRootAsync, the values
+    /// are reloaded on `game-root-changed`.
+    public async Task<GamePaths?> SelectInstallationAsync(string name)
+    {
+        return await _tauri.Core.Invoke<GamePaths>("select_installation", new { name });
+    }
+
+    /// Saved changes, newest first.
+    public async Task<List<JournalRecord>> GetChangeHistoryAsync(int? limit = null)
+    {
+        return await _tauri.Core.Invoke<List<JournalRecord>>("get_change_history", new { limit })
+            ?? new();
     }
 }